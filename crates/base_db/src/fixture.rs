@@ -111,6 +111,7 @@ impl ChangeFixture {
         let mut crate_deps = Vec::new();
         let mut default_crate_root: Option<FileId> = None;
         let mut default_cfg = CfgOptions::default();
+        let mut default_is_proc_macro = false;
 
         let mut file_set = FileSet::default();
         let mut current_source_root_kind = SourceRootKind::Local;
@@ -160,7 +161,7 @@ impl ChangeFixture {
                     meta.cfg,
                     meta.env,
                     Default::default(),
-                    false,
+                    meta.is_proc_macro,
                     origin,
                 );
                 let prev = crates.insert(crate_name.clone(), crate_id);
@@ -174,6 +175,7 @@ impl ChangeFixture {
                 assert!(default_crate_root.is_none());
                 default_crate_root = Some(file_id);
                 default_cfg = meta.cfg;
+                default_is_proc_macro = meta.is_proc_macro;
             }
 
             change.change_file(file_id, Some(Arc::new(text)));
@@ -195,7 +197,7 @@ impl ChangeFixture {
                 default_cfg,
                 Env::default(),
                 Default::default(),
-                false,
+                default_is_proc_macro,
                 Default::default(),
             );
         } else {
@@ -391,6 +393,7 @@ struct FileMeta {
     edition: Edition,
     env: Env,
     introduce_new_source_root: Option<SourceRootKind>,
+    is_proc_macro: bool,
 }
 
 fn parse_crate(crate_str: String) -> (String, CrateOrigin, Option<String>) {
@@ -429,6 +432,11 @@ impl From<Fixture> for FileMeta {
                 "library" => SourceRootKind::Library,
                 invalid => panic!("invalid source root kind '{}'", invalid),
             }),
+            is_proc_macro: match f.crate_type.as_deref() {
+                None | Some("lib") => false,
+                Some("proc-macro") => true,
+                Some(invalid) => panic!("invalid crate type '{}'", invalid),
+            },
         }
     }
 }
@@ -150,6 +150,10 @@ impl Crate {
         db.crate_graph()[self.id].origin.clone()
     }
 
+    pub fn is_proc_macro(self, db: &dyn HirDatabase) -> bool {
+        db.crate_graph()[self.id].is_proc_macro
+    }
+
     pub fn dependencies(self, db: &dyn HirDatabase) -> Vec<CrateDependency> {
         db.crate_graph()[self.id]
             .dependencies
@@ -1811,6 +1815,27 @@ impl Macro {
     pub fn is_attr(&self, db: &dyn HirDatabase) -> bool {
         matches!(self.kind(db), MacroKind::Attr)
     }
+
+    /// The helper attributes declared by a derive macro, empty for builtin
+    /// derives and the other kinds of macros.
+    pub fn derive_helpers(&self, db: &dyn HirDatabase) -> Vec<Name> {
+        match self.id {
+            MacroId::ProcMacroId(id) => db.proc_macro_data(id).derive_helpers.to_vec(),
+            MacroId::Macro2Id(_) | MacroId::MacroRulesId(_) => Vec::new(),
+        }
+    }
+
+    pub fn is_builtin_attr(&self, db: &dyn HirDatabase) -> bool {
+        match self.id {
+            MacroId::Macro2Id(it) => {
+                matches!(it.lookup(db.upcast()).expander, MacroExpander::BuiltInAttr(_))
+            }
+            MacroId::MacroRulesId(it) => {
+                matches!(it.lookup(db.upcast()).expander, MacroExpander::BuiltInAttr(_))
+            }
+            MacroId::ProcMacroId(_) => false,
+        }
+    }
 }
 
 impl HasVisibility for Macro {
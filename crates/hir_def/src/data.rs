@@ -332,7 +332,8 @@ impl MacroRulesData {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ProcMacroData {
     pub name: Name,
-    // FIXME: Record deriver helper here?
+    /// The helper attributes of a derive macro, empty for the other kinds.
+    pub derive_helpers: Box<[Name]>,
 }
 
 impl ProcMacroData {
@@ -344,17 +345,17 @@ impl ProcMacroData {
         let item_tree = loc.id.item_tree(db);
         let makro = &item_tree[loc.id.value];
 
-        let name = if let Some(def) = item_tree
+        let (name, derive_helpers) = if let Some(def) = item_tree
             .attrs(db, loc.container.krate(), ModItem::from(loc.id.value).into())
             .parse_proc_macro_decl(&makro.name)
         {
-            def.name
+            (def.name.clone(), def.into_derive_helpers())
         } else {
             // eeeh...
             stdx::never!("proc macro declaration is not a proc macro");
-            makro.name.clone()
+            (makro.name.clone(), Box::new([]) as Box<[_]>)
         };
-        Arc::new(ProcMacroData { name })
+        Arc::new(ProcMacroData { name, derive_helpers })
     }
 }
 
@@ -335,33 +335,34 @@ macro_rules! m {
 fn bar() {
     (1+2)*3;
 }
-// MACRO_ITEMS@0..17
-//   FN@0..17
+// MACRO_ITEMS@0..18
+//   FN@0..18
 //     FN_KW@0..2 "fn"
-//     NAME@2..5
-//       IDENT@2..5 "bar"
-//     PARAM_LIST@5..7
-//       L_PAREN@5..6 "("
-//       R_PAREN@6..7 ")"
-//     BLOCK_EXPR@7..17
-//       STMT_LIST@7..17
-//         L_CURLY@7..8 "{"
-//         EXPR_STMT@8..16
-//           BIN_EXPR@8..15
-//             PAREN_EXPR@8..13
-//               L_PAREN@8..9 "("
-//               BIN_EXPR@9..12
-//                 LITERAL@9..10
-//                   INT_NUMBER@9..10 "1"
-//                 PLUS@10..11 "+"
-//                 LITERAL@11..12
-//                   INT_NUMBER@11..12 "2"
-//               R_PAREN@12..13 ")"
-//             STAR@13..14 "*"
-//             LITERAL@14..15
-//               INT_NUMBER@14..15 "3"
-//           SEMICOLON@15..16 ";"
-//         R_CURLY@16..17 "}"
+//     WHITESPACE@2..3 " "
+//     NAME@3..6
+//       IDENT@3..6 "bar"
+//     PARAM_LIST@6..8
+//       L_PAREN@6..7 "("
+//       R_PAREN@7..8 ")"
+//     BLOCK_EXPR@8..18
+//       STMT_LIST@8..18
+//         L_CURLY@8..9 "{"
+//         EXPR_STMT@9..17
+//           BIN_EXPR@9..16
+//             PAREN_EXPR@9..14
+//               L_PAREN@9..10 "("
+//               BIN_EXPR@10..13
+//                 LITERAL@10..11
+//                   INT_NUMBER@10..11 "1"
+//                 PLUS@11..12 "+"
+//                 LITERAL@12..13
+//                   INT_NUMBER@12..13 "2"
+//               R_PAREN@13..14 ")"
+//             STAR@14..15 "*"
+//             LITERAL@15..16
+//               INT_NUMBER@15..16 "3"
+//           SEMICOLON@16..17 ";"
+//         R_CURLY@17..18 "}"
 
 "#]],
     )
@@ -458,39 +459,41 @@ struct Foo {
 struct Bar {
     field: u32
 }
-// MACRO_ITEMS@0..40
-//   STRUCT@0..20
+// MACRO_ITEMS@0..42
+//   STRUCT@0..21
 //     STRUCT_KW@0..6 "struct"
-//     NAME@6..9
-//       IDENT@6..9 "Foo"
-//     RECORD_FIELD_LIST@9..20
-//       L_CURLY@9..10 "{"
-//       RECORD_FIELD@10..19
-//         NAME@10..15
-//           IDENT@10..15 "field"
-//         COLON@15..16 ":"
-//         PATH_TYPE@16..19
-//           PATH@16..19
-//             PATH_SEGMENT@16..19
-//               NAME_REF@16..19
-//                 IDENT@16..19 "u32"
-//       R_CURLY@19..20 "}"
-//   STRUCT@20..40
-//     STRUCT_KW@20..26 "struct"
-//     NAME@26..29
-//       IDENT@26..29 "Bar"
-//     RECORD_FIELD_LIST@29..40
-//       L_CURLY@29..30 "{"
-//       RECORD_FIELD@30..39
-//         NAME@30..35
-//           IDENT@30..35 "field"
-//         COLON@35..36 ":"
-//         PATH_TYPE@36..39
-//           PATH@36..39
-//             PATH_SEGMENT@36..39
-//               NAME_REF@36..39
-//                 IDENT@36..39 "u32"
-//       R_CURLY@39..40 "}"
+//     WHITESPACE@6..7 " "
+//     NAME@7..10
+//       IDENT@7..10 "Foo"
+//     RECORD_FIELD_LIST@10..21
+//       L_CURLY@10..11 "{"
+//       RECORD_FIELD@11..20
+//         NAME@11..16
+//           IDENT@11..16 "field"
+//         COLON@16..17 ":"
+//         PATH_TYPE@17..20
+//           PATH@17..20
+//             PATH_SEGMENT@17..20
+//               NAME_REF@17..20
+//                 IDENT@17..20 "u32"
+//       R_CURLY@20..21 "}"
+//   STRUCT@21..42
+//     STRUCT_KW@21..27 "struct"
+//     WHITESPACE@27..28 " "
+//     NAME@28..31
+//       IDENT@28..31 "Bar"
+//     RECORD_FIELD_LIST@31..42
+//       L_CURLY@31..32 "{"
+//       RECORD_FIELD@32..41
+//         NAME@32..37
+//           IDENT@32..37 "field"
+//         COLON@37..38 ":"
+//         PATH_TYPE@38..41
+//           PATH@38..41
+//             PATH_SEGMENT@38..41
+//               NAME_REF@38..41
+//                 IDENT@38..41 "u32"
+//       R_CURLY@41..42 "}"
 
             "#]],
     );
@@ -547,36 +550,37 @@ fn f() -> i32 {
     let a = 0;
     a = 10+1;
     a
-// MACRO_STMTS@0..15
-//   LET_STMT@0..7
+// MACRO_STMTS@0..16
+//   LET_STMT@0..8
 //     LET_KW@0..3 "let"
-//     IDENT_PAT@3..4
-//       NAME@3..4
-//         IDENT@3..4 "a"
-//     EQ@4..5 "="
-//     LITERAL@5..6
-//       INT_NUMBER@5..6 "0"
-//     SEMICOLON@6..7 ";"
-//   EXPR_STMT@7..14
-//     BIN_EXPR@7..13
-//       PATH_EXPR@7..8
-//         PATH@7..8
-//           PATH_SEGMENT@7..8
-//             NAME_REF@7..8
-//               IDENT@7..8 "a"
-//       EQ@8..9 "="
-//       BIN_EXPR@9..13
-//         LITERAL@9..11
-//           INT_NUMBER@9..11 "10"
-//         PLUS@11..12 "+"
-//         LITERAL@12..13
-//           INT_NUMBER@12..13 "1"
-//     SEMICOLON@13..14 ";"
-//   PATH_EXPR@14..15
-//     PATH@14..15
-//       PATH_SEGMENT@14..15
-//         NAME_REF@14..15
-//           IDENT@14..15 "a"
+//     WHITESPACE@3..4 " "
+//     IDENT_PAT@4..5
+//       NAME@4..5
+//         IDENT@4..5 "a"
+//     EQ@5..6 "="
+//     LITERAL@6..7
+//       INT_NUMBER@6..7 "0"
+//     SEMICOLON@7..8 ";"
+//   EXPR_STMT@8..15
+//     BIN_EXPR@8..14
+//       PATH_EXPR@8..9
+//         PATH@8..9
+//           PATH_SEGMENT@8..9
+//             NAME_REF@8..9
+//               IDENT@8..9 "a"
+//       EQ@9..10 "="
+//       BIN_EXPR@10..14
+//         LITERAL@10..12
+//           INT_NUMBER@10..12 "10"
+//         PLUS@12..13 "+"
+//         LITERAL@13..14
+//           INT_NUMBER@13..14 "1"
+//     SEMICOLON@14..15 ";"
+//   PATH_EXPR@15..16
+//     PATH@15..16
+//       PATH_SEGMENT@15..16
+//         NAME_REF@15..16
+//           IDENT@15..16 "a"
 
 }
 "#]],
@@ -836,7 +840,7 @@ macro_rules! m {
 
 fn bar() -> & 'a Baz<u8> {}
 
-fn bar() -> extern "Rust"fn() -> Ret {}
+fn bar() -> extern "Rust" fn() -> Ret {}
 "#]],
     );
 }
@@ -1394,43 +1398,44 @@ macro_rules! m {
 fn f() {
     K::(C("0"));
 }
-// MACRO_ITEMS@0..19
-//   FN@0..19
+// MACRO_ITEMS@0..20
+//   FN@0..20
 //     FN_KW@0..2 "fn"
-//     NAME@2..3
-//       IDENT@2..3 "f"
-//     PARAM_LIST@3..5
-//       L_PAREN@3..4 "("
-//       R_PAREN@4..5 ")"
-//     BLOCK_EXPR@5..19
-//       STMT_LIST@5..19
-//         L_CURLY@5..6 "{"
-//         EXPR_STMT@6..10
-//           PATH_EXPR@6..10
-//             PATH@6..10
-//               PATH@6..7
-//                 PATH_SEGMENT@6..7
-//                   NAME_REF@6..7
-//                     IDENT@6..7 "K"
-//               COLON2@7..9 "::"
-//               ERROR@9..10
-//                 L_PAREN@9..10 "("
-//         EXPR_STMT@10..16
-//           CALL_EXPR@10..16
-//             PATH_EXPR@10..11
-//               PATH@10..11
-//                 PATH_SEGMENT@10..11
-//                   NAME_REF@10..11
-//                     IDENT@10..11 "C"
-//             ARG_LIST@11..16
-//               L_PAREN@11..12 "("
-//               LITERAL@12..15
-//                 STRING@12..15 "\"0\""
-//               R_PAREN@15..16 ")"
-//         ERROR@16..17
-//           R_PAREN@16..17 ")"
-//         SEMICOLON@17..18 ";"
-//         R_CURLY@18..19 "}"
+//     WHITESPACE@2..3 " "
+//     NAME@3..4
+//       IDENT@3..4 "f"
+//     PARAM_LIST@4..6
+//       L_PAREN@4..5 "("
+//       R_PAREN@5..6 ")"
+//     BLOCK_EXPR@6..20
+//       STMT_LIST@6..20
+//         L_CURLY@6..7 "{"
+//         EXPR_STMT@7..11
+//           PATH_EXPR@7..11
+//             PATH@7..11
+//               PATH@7..8
+//                 PATH_SEGMENT@7..8
+//                   NAME_REF@7..8
+//                     IDENT@7..8 "K"
+//               COLON2@8..10 "::"
+//               ERROR@10..11
+//                 L_PAREN@10..11 "("
+//         EXPR_STMT@11..17
+//           CALL_EXPR@11..17
+//             PATH_EXPR@11..12
+//               PATH@11..12
+//                 PATH_SEGMENT@11..12
+//                   NAME_REF@11..12
+//                     IDENT@11..12 "C"
+//             ARG_LIST@12..17
+//               L_PAREN@12..13 "("
+//               LITERAL@13..16
+//                 STRING@13..16 "\"0\""
+//               R_PAREN@16..17 ")"
+//         ERROR@17..18
+//           R_PAREN@17..18 ")"
+//         SEMICOLON@18..19 ";"
+//         R_CURLY@19..20 "}"
 
 "#]],
     );
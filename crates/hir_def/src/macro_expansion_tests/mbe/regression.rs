@@ -297,55 +297,55 @@ macro_rules! impl_fn_for_zst  {
 
 #[derive(Clone)] struct CharEscapeDebugContinue;
 impl Fn<(char, )> for CharEscapeDebugContinue {
-    #[inline] extern "rust-call"fn call(&self , (c, ): (char, )) -> char::EscapeDebug { {
+    #[inline] extern "rust-call" fn call(&self , (c, ): (char, )) -> char::EscapeDebug { {
             c.escape_debug_ext(false )
         }
     }
 }
 impl FnMut<(char, )> for CharEscapeDebugContinue {
-    #[inline] extern "rust-call"fn call_mut(&mut self , (c, ): (char, )) -> char::EscapeDebug {
+    #[inline] extern "rust-call" fn call_mut(&mut self , (c, ): (char, )) -> char::EscapeDebug {
         Fn::call(&*self , (c, ))
     }
 }
 impl FnOnce<(char, )> for CharEscapeDebugContinue {
     type Output = char::EscapeDebug;
-    #[inline] extern "rust-call"fn call_once(self , (c, ): (char, )) -> char::EscapeDebug {
+    #[inline] extern "rust-call" fn call_once(self , (c, ): (char, )) -> char::EscapeDebug {
         Fn::call(&self , (c, ))
     }
 }
 #[derive(Clone)] struct CharEscapeUnicode;
 impl Fn<(char, )> for CharEscapeUnicode {
-    #[inline] extern "rust-call"fn call(&self , (c, ): (char, )) -> char::EscapeUnicode { {
+    #[inline] extern "rust-call" fn call(&self , (c, ): (char, )) -> char::EscapeUnicode { {
             c.escape_unicode()
         }
     }
 }
 impl FnMut<(char, )> for CharEscapeUnicode {
-    #[inline] extern "rust-call"fn call_mut(&mut self , (c, ): (char, )) -> char::EscapeUnicode {
+    #[inline] extern "rust-call" fn call_mut(&mut self , (c, ): (char, )) -> char::EscapeUnicode {
         Fn::call(&*self , (c, ))
     }
 }
 impl FnOnce<(char, )> for CharEscapeUnicode {
     type Output = char::EscapeUnicode;
-    #[inline] extern "rust-call"fn call_once(self , (c, ): (char, )) -> char::EscapeUnicode {
+    #[inline] extern "rust-call" fn call_once(self , (c, ): (char, )) -> char::EscapeUnicode {
         Fn::call(&self , (c, ))
     }
 }
 #[derive(Clone)] struct CharEscapeDefault;
 impl Fn<(char, )> for CharEscapeDefault {
-    #[inline] extern "rust-call"fn call(&self , (c, ): (char, )) -> char::EscapeDefault { {
+    #[inline] extern "rust-call" fn call(&self , (c, ): (char, )) -> char::EscapeDefault { {
             c.escape_default()
         }
     }
 }
 impl FnMut<(char, )> for CharEscapeDefault {
-    #[inline] extern "rust-call"fn call_mut(&mut self , (c, ): (char, )) -> char::EscapeDefault {
+    #[inline] extern "rust-call" fn call_mut(&mut self , (c, ): (char, )) -> char::EscapeDefault {
         Fn::call(&*self , (c, ))
     }
 }
 impl FnOnce<(char, )> for CharEscapeDefault {
     type Output = char::EscapeDefault;
-    #[inline] extern "rust-call"fn call_once(self , (c, ): (char, )) -> char::EscapeDefault {
+    #[inline] extern "rust-call" fn call_once(self , (c, ): (char, )) -> char::EscapeDefault {
         Fn::call(&self , (c, ))
     }
 }
@@ -724,7 +724,7 @@ macro_rules! delegate_impl {
         }
     }
 }
-impl <> Data for & 'amut G where G: Data {}
+impl <> Data for & 'a mut G where G: Data {}
 "##]],
     );
 }
@@ -833,57 +833,62 @@ macro_rules! rgb_color {
 /* parse error: expected SEMICOLON */
 /* parse error: expected expression */
 pub fn new() {
-    let _ = 0as u32<<(8+8);
+    let _ = 0 as u32<<(8+8);
 }
-// MACRO_ITEMS@0..31
-//   FN@0..31
-//     VISIBILITY@0..3
+// MACRO_ITEMS@0..36
+//   FN@0..36
+//     VISIBILITY@0..4
 //       PUB_KW@0..3 "pub"
-//     FN_KW@3..5 "fn"
-//     NAME@5..8
-//       IDENT@5..8 "new"
-//     PARAM_LIST@8..10
-//       L_PAREN@8..9 "("
-//       R_PAREN@9..10 ")"
-//     BLOCK_EXPR@10..31
-//       STMT_LIST@10..31
-//         L_CURLY@10..11 "{"
-//         LET_STMT@11..27
-//           LET_KW@11..14 "let"
-//           WILDCARD_PAT@14..15
-//             UNDERSCORE@14..15 "_"
-//           EQ@15..16 "="
-//           CAST_EXPR@16..27
-//             LITERAL@16..17
-//               INT_NUMBER@16..17 "0"
-//             AS_KW@17..19 "as"
-//             PATH_TYPE@19..27
-//               PATH@19..27
-//                 PATH_SEGMENT@19..27
-//                   NAME_REF@19..22
-//                     IDENT@19..22 "u32"
-//                   GENERIC_ARG_LIST@22..27
-//                     L_ANGLE@22..23 "<"
-//                     TYPE_ARG@23..27
-//                       DYN_TRAIT_TYPE@23..27
-//                         TYPE_BOUND_LIST@23..27
-//                           TYPE_BOUND@23..26
-//                             PATH_TYPE@23..26
-//                               PATH@23..26
-//                                 PATH_SEGMENT@23..26
-//                                   L_ANGLE@23..24 "<"
-//                                   PAREN_TYPE@24..26
-//                                     L_PAREN@24..25 "("
-//                                     ERROR@25..26
-//                                       INT_NUMBER@25..26 "8"
-//                           PLUS@26..27 "+"
-//         EXPR_STMT@27..28
-//           LITERAL@27..28
-//             INT_NUMBER@27..28 "8"
-//         ERROR@28..29
-//           R_PAREN@28..29 ")"
-//         SEMICOLON@29..30 ";"
-//         R_CURLY@30..31 "}"
+//       WHITESPACE@3..4 " "
+//     FN_KW@4..6 "fn"
+//     WHITESPACE@6..7 " "
+//     NAME@7..10
+//       IDENT@7..10 "new"
+//     PARAM_LIST@10..12
+//       L_PAREN@10..11 "("
+//       R_PAREN@11..12 ")"
+//     BLOCK_EXPR@12..36
+//       STMT_LIST@12..36
+//         L_CURLY@12..13 "{"
+//         LET_STMT@13..32
+//           LET_KW@13..16 "let"
+//           WHITESPACE@16..17 " "
+//           WILDCARD_PAT@17..18
+//             UNDERSCORE@17..18 "_"
+//           EQ@18..19 "="
+//           CAST_EXPR@19..32
+//             LITERAL@19..21
+//               INT_NUMBER@19..20 "0"
+//               WHITESPACE@20..21 " "
+//             AS_KW@21..23 "as"
+//             WHITESPACE@23..24 " "
+//             PATH_TYPE@24..32
+//               PATH@24..32
+//                 PATH_SEGMENT@24..32
+//                   NAME_REF@24..27
+//                     IDENT@24..27 "u32"
+//                   GENERIC_ARG_LIST@27..32
+//                     L_ANGLE@27..28 "<"
+//                     TYPE_ARG@28..32
+//                       DYN_TRAIT_TYPE@28..32
+//                         TYPE_BOUND_LIST@28..32
+//                           TYPE_BOUND@28..31
+//                             PATH_TYPE@28..31
+//                               PATH@28..31
+//                                 PATH_SEGMENT@28..31
+//                                   L_ANGLE@28..29 "<"
+//                                   PAREN_TYPE@29..31
+//                                     L_PAREN@29..30 "("
+//                                     ERROR@30..31
+//                                       INT_NUMBER@30..31 "8"
+//                           PLUS@31..32 "+"
+//         EXPR_STMT@32..33
+//           LITERAL@32..33
+//             INT_NUMBER@32..33 "8"
+//         ERROR@33..34
+//           R_PAREN@33..34 ")"
+//         SEMICOLON@34..35 ";"
+//         R_CURLY@35..36 "}"
 
 "#]],
     );
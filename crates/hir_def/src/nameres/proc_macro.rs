@@ -28,6 +28,16 @@ impl ProcMacroKind {
     }
 }
 
+impl ProcMacroDef {
+    /// The helper attributes declared by a derive, none for the other kinds.
+    pub(crate) fn into_derive_helpers(self) -> Box<[Name]> {
+        match self.kind {
+            ProcMacroKind::CustomDerive { helpers } => helpers,
+            ProcMacroKind::FnLike | ProcMacroKind::Attr => Box::new([]),
+        }
+    }
+}
+
 impl Attrs {
     #[rustfmt::skip]
     pub fn parse_proc_macro_decl(&self, func_name: &Name) -> Option<ProcMacroDef> {
@@ -306,10 +306,10 @@ fn expr_macro_expanded_in_stmts() {
         }
         "#,
         expect![[r#"
-            !0..8 'leta=();': ()
-            !0..8 'leta=();': ()
-            !3..4 'a': ()
-            !5..7 '()': ()
+            !0..9 'let a=();': ()
+            !0..9 'let a=();': ()
+            !4..5 'a': ()
+            !6..8 '()': ()
             57..84 '{     ...); } }': ()
         "#]],
     );
@@ -335,13 +335,13 @@ fn recurisve_macro_expanded_in_stmts() {
         }
         "#,
         expect![[r#"
-            !0..7 'leta=3;': {unknown}
-            !0..7 'leta=3;': {unknown}
-            !0..13 'ng!{[leta=3]}': {unknown}
-            !0..13 'ng!{[leta=]3}': {unknown}
-            !0..13 'ng!{[leta]=3}': {unknown}
-            !3..4 'a': i32
-            !5..6 '3': i32
+            !0..8 'let a=3;': {unknown}
+            !0..8 'let a=3;': {unknown}
+            !0..14 'ng!{[let a=3]}': {unknown}
+            !0..14 'ng!{[let a=]3}': {unknown}
+            !0..14 'ng!{[let a]=3}': {unknown}
+            !4..5 'a': i32
+            !6..7 '3': i32
             196..237 '{     ...= a; }': ()
             229..230 'b': i32
             233..234 'a': i32
@@ -387,11 +387,11 @@ fn infer_macro_defining_block_with_items() {
         }
     "#,
         expect![[r#"
-            !15..18 '{0}': usize
-            !16..17 '0': usize
-            !0..24 '{fnbar...bar()}': usize
-            !18..21 'bar': fn bar() -> usize
-            !18..23 'bar()': usize
+            !16..19 '{0}': usize
+            !17..18 '0': usize
+            !0..25 '{fn ba...bar()}': usize
+            !19..22 'bar': fn bar() -> usize
+            !19..24 'bar()': usize
             98..122 '{     ...!(); }': ()
             108..110 '_a': usize
         "#]],
@@ -575,11 +575,11 @@ fn issue_4465_dollar_crate_at_type() {
             44..59 '{     loop {} }': T
             50..57 'loop {}': !
             55..57 '{}': ()
-            !0..31 '{letr:...g();r}': Foo
-            !4..5 'r': Foo
-            !18..26 'anything': fn anything<Foo>() -> Foo
-            !18..28 'anything()': Foo
-            !29..30 'r': Foo
+            !0..32 '{let r...g();r}': Foo
+            !5..6 'r': Foo
+            !19..27 'anything': fn anything<Foo>() -> Foo
+            !19..29 'anything()': Foo
+            !30..31 'r': Foo
             163..187 '{     ...!(); }': ()
             173..175 '_a': Foo
         "#]],
@@ -601,10 +601,10 @@ fn issue_6811() {
         }
         "#,
         expect![[r#"
-            !3..5 '_a': i32
-            !6..7 '1': i32
-            !11..13 '_b': i32
-            !14..15 '1': i32
+            !4..6 '_a': i32
+            !7..8 '1': i32
+            !13..15 '_b': i32
+            !16..17 '1': i32
             103..131 '{     ...!(); }': ()
         "#]],
     );
@@ -2,10 +2,11 @@
 //!
 //! This module uses a bit of static metadata to provide completions for builtin-in attributes and lints.
 
+use std::iter;
+
 use ide_db::{
-    generated::lints::{
-        Lint, CLIPPY_LINTS, CLIPPY_LINT_GROUPS, DEFAULT_LINTS, FEATURES, RUSTDOC_LINTS,
-    },
+    base_db::Edition,
+    generated::lints::{Lint, CLIPPY_LINTS, CLIPPY_LINT_GROUPS, DEFAULT_LINTS, RUSTDOC_LINTS},
     syntax_helpers::node_ext::parse_tt_as_comma_sep_paths,
     SymbolKind,
 };
@@ -13,9 +14,11 @@ use itertools::Itertools;
 use once_cell::sync::Lazy;
 use rustc_hash::FxHashMap;
 use syntax::{
-    ast::{self, AttrKind},
-    AstNode, SyntaxKind, T,
+    algo::non_trivia_sibling,
+    ast::{self, AttrKind, HasAttrs, HasName},
+    AstNode, Direction, NodeOrToken, SmolStr, SyntaxKind, TextSize, T,
 };
+use text_edit::TextEdit;
 
 use crate::{
     completions::module_or_attr,
@@ -26,8 +29,12 @@ use crate::{
 
 mod cfg;
 mod derive;
+mod limit;
+mod link;
 mod lint;
+mod macro_use;
 mod repr;
+mod target_feature;
 
 /// Complete inputs to known builtin attributes as well as derive attributes
 pub(crate) fn complete_known_attribute_input(
@@ -39,17 +46,38 @@ pub(crate) fn complete_known_attribute_input(
         Some(p) => Some(p.as_single_name_ref()?),
         None => None,
     };
+    if attribute.expr().is_some() {
+        let name = name_ref?;
+        limit::complete_limit(acc, ctx, name.text().as_str());
+        return Some(());
+    }
     let (path, tt) = name_ref.zip(attribute.token_tree())?;
+    let (path, tt) = match attr_input_at_caret(ctx, path.text().into(), tt)? {
+        AttrInput::CfgPredicate => {
+            cfg::complete_cfg(acc, ctx);
+            return Some(());
+        }
+        AttrInput::Attr(path, tt) => (path, tt),
+    };
     if tt.l_paren_token().is_none() {
         return None;
     }
 
-    match path.text().as_str() {
+    match path.as_str() {
         "repr" => repr::complete_repr(acc, ctx, tt),
-        "derive" => derive::complete_derive(acc, ctx, ctx.attr.as_ref()?),
-        "feature" => lint::complete_lint(acc, ctx, &parse_tt_as_comma_sep_paths(tt)?, FEATURES),
-        "allow" | "warn" | "deny" | "forbid" => {
-            let existing_lints = parse_tt_as_comma_sep_paths(tt)?;
+        "derive" => match nested_tt_at(&tt, ctx.position.offset) {
+            Some(args) => derive::complete_derive_args(acc, ctx, &args),
+            None => derive::complete_derive(
+                acc,
+                ctx,
+                ctx.attr.as_ref()?,
+                &parse_tt_as_comma_sep_paths(tt)?,
+            ),
+        },
+        "link" => link::complete_link(acc, ctx, &tt),
+        "feature" => lint::complete_feature(acc, ctx, &parse_tt_as_comma_sep_paths(tt)?),
+        "allow" | "warn" | "deny" | "forbid" | "expect" => {
+            let existing_lints = parse_tt_as_comma_sep_paths(tt.clone())?;
 
             let lints: Vec<Lint> = CLIPPY_LINT_GROUPS
                 .iter()
@@ -61,15 +89,167 @@ pub(crate) fn complete_known_attribute_input(
                 .collect();
 
             lint::complete_lint(acc, ctx, &existing_lints, &lints);
+            lint::complete_lint_reason(acc, ctx, &tt);
         }
-        "cfg" => {
-            cfg::complete_cfg(acc, ctx);
-        }
+        "macro_use" => macro_use::complete_macro_use(acc, ctx, &parse_tt_as_comma_sep_paths(tt)?),
+        "doc" if nested_tt_at(&tt, ctx.position.offset).is_none() => complete_doc_keys(acc, ctx),
+        "target_feature" => target_feature::complete_target_feature(acc, ctx),
+        "cfg_attr" => complete_cfg_attr_slot(acc, ctx, &tt),
+        "instruction_set" => complete_instruction_set(acc, ctx),
+        "coverage" => ["on", "off"].into_iter().for_each(|mode| {
+            acc.add(CompletionItem::new(SymbolKind::BuiltinAttr, ctx.source_range(), mode).build())
+        }),
         _ => (),
     }
     Some(())
 }
 
+/// Completes the attribute names in the slots after the predicate of `#[cfg_attr(...)]`,
+/// leaving out the attributes already listed in it.
+fn complete_cfg_attr_slot(acc: &mut Completions, ctx: &CompletionContext, tt: &ast::TokenTree) {
+    let attr = match &ctx.attr {
+        Some(it) => it,
+        None => return,
+    };
+    let offset = ctx.position.offset;
+    let before_caret = match ctx.original_token.kind() {
+        SyntaxKind::IDENT => ctx.original_token.prev_token(),
+        _ => Some(ctx.original_token.clone()),
+    };
+    let at_slot_start = iter::successors(before_caret, |it| it.prev_token())
+        .find(|it| !it.kind().is_trivia())
+        .map_or(false, |it| it.kind() == T![,] && it.text_range().end() <= offset);
+    if !at_slot_start {
+        return;
+    }
+
+    // the first name of every slot, the one under the caret excluded
+    let existing: Vec<SmolStr> = tt
+        .syntax()
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| it.kind() == T![,])
+        .filter_map(|comma| {
+            iter::successors(comma.next_token(), |it| it.next_token())
+                .find(|it| !it.kind().is_trivia())
+        })
+        .filter(|it| it.kind() == SyntaxKind::IDENT && !it.text_range().contains_inclusive(offset))
+        .map(|it| it.text().into())
+        .collect();
+    let is_inner = attr.kind() == AttrKind::Inner;
+    let annotated_item_kind = attr.syntax().parent().map(|it| it.kind());
+    complete_builtin_attributes(acc, ctx, is_inner, annotated_item_kind, &existing);
+}
+
+/// Completes the keys of `#[doc(...)]`, offering `cfg(…)` only with the `doc_cfg` feature.
+fn complete_doc_keys(acc: &mut Completions, ctx: &CompletionContext) {
+    let keys = [
+        (r#"alias = "…""#, "alias", r#"alias = "${0:name}""#),
+        ("cfg(…)", "cfg", "cfg(${0:predicate})"),
+        ("hidden", "hidden", "hidden"),
+        ("inline", "inline", "inline"),
+        ("no_inline", "no_inline", "no_inline"),
+    ];
+    for (label, lookup, snippet) in keys {
+        if lookup == "cfg" && !ctx.is_feature_enabled("doc_cfg") {
+            continue;
+        }
+        let mut item = CompletionItem::new(SymbolKind::BuiltinAttr, ctx.source_range(), label);
+        item.lookup_by(lookup);
+        if let Some(cap) = ctx.config.snippet_cap {
+            item.insert_snippet(cap, snippet);
+        }
+        item.add_to(acc);
+    }
+}
+
+/// Completes the `arm::a32` and `arm::t32` instruction sets of a `fn`, only
+/// offered when compiling for 32-bit ARM, the one target supporting them.
+fn complete_instruction_set(acc: &mut Completions, ctx: &CompletionContext) {
+    let annotated_item_kind =
+        ctx.attr.as_ref().and_then(|attr| attr.syntax().parent()).map(|it| it.kind());
+    let is_arm = ctx.krate.map_or(false, |krate| {
+        krate.cfg(ctx.db).get_cfg_values("target_arch").any(|arch| arch == "arm")
+    });
+    if annotated_item_kind != Some(SyntaxKind::FN) || !is_arm {
+        return;
+    }
+
+    // after `arm::` only the last segment is left to complete, token trees hold
+    // the two colons as separate tokens
+    let before_caret = match ctx.original_token.kind() {
+        SyntaxKind::IDENT => ctx.original_token.prev_token(),
+        _ => Some(ctx.original_token.clone()),
+    };
+    let after_arm = before_caret.map_or(false, |colon| {
+        colon.kind() == T![:] && colon.prev_token().map_or(false, |it| it.kind() == T![:])
+    });
+    for set in ["a32", "t32"] {
+        let label = if after_arm { SmolStr::new(set) } else { format!("arm::{}", set).into() };
+        acc.add(CompletionItem::new(SymbolKind::BuiltinAttr, ctx.source_range(), label).build());
+    }
+}
+
+/// The token tree directly nested in `tt` whose delimiters surround `offset`.
+fn nested_tt_at(tt: &ast::TokenTree, offset: TextSize) -> Option<ast::TokenTree> {
+    tt.syntax().children().filter_map(ast::TokenTree::cast).find(|it| {
+        let range = it.syntax().text_range();
+        range.start() < offset && offset < range.end()
+    })
+}
+
+enum AttrInput {
+    /// The caret is inside a `cfg` predicate.
+    CfgPredicate,
+    /// The caret is inside the input of the attribute with the given name.
+    Attr(SmolStr, ast::TokenTree),
+}
+
+/// Finds out what the caret is completing within the input `tt` of the attribute `name`,
+/// descending into the attributes nested in (possibly nested) `cfg_attr`s.
+fn attr_input_at_caret(
+    ctx: &CompletionContext,
+    mut name: SmolStr,
+    mut tt: ast::TokenTree,
+) -> Option<AttrInput> {
+    let offset = ctx.position.offset;
+    loop {
+        match name.as_str() {
+            "cfg" => return Some(AttrInput::CfgPredicate),
+            "cfg_attr" => (),
+            "doc" if ctx.is_feature_enabled("doc_cfg") => {
+                let in_cfg = nested_tt_at(&tt, offset)
+                    .and_then(|it| non_trivia_sibling(it.syntax().clone().into(), Direction::Prev))
+                    .and_then(|it| it.into_token())
+                    .map_or(false, |it| it.kind() == SyntaxKind::IDENT && it.text() == "cfg");
+                return Some(if in_cfg {
+                    AttrInput::CfgPredicate
+                } else {
+                    AttrInput::Attr(name, tt)
+                });
+            }
+            _ => return Some(AttrInput::Attr(name, tt)),
+        }
+        let preceding_commas = tt
+            .syntax()
+            .children_with_tokens()
+            .filter(|it| it.kind() == T![,] && it.text_range().end() <= offset)
+            .count();
+        if preceding_commas == 0 {
+            return Some(AttrInput::CfgPredicate);
+        }
+        tt = match nested_tt_at(&tt, offset) {
+            Some(it) => it,
+            None => return Some(AttrInput::Attr(name, tt)),
+        };
+        let nested_name = non_trivia_sibling(tt.syntax().clone().into(), Direction::Prev)?;
+        name = match nested_name.into_token() {
+            Some(it) if it.kind() == SyntaxKind::IDENT => it.text().into(),
+            _ => return None,
+        };
+    }
+}
+
 pub(crate) fn complete_attribute(acc: &mut Completions, ctx: &CompletionContext) {
     let (is_absolute_path, qualifier, is_inner, annotated_item_kind) = match ctx.path_context {
         Some(PathCompletionCtx {
@@ -81,6 +261,7 @@ pub(crate) fn complete_attribute(acc: &mut Completions, ctx: &CompletionContext)
         _ => return,
     };
 
+    let mut attr_macros_in_scope = Vec::new();
     match qualifier {
         Some(PathQualifierCtx { resolution, is_super_chain, .. }) => {
             if *is_super_chain {
@@ -89,6 +270,13 @@ pub(crate) fn complete_attribute(acc: &mut Completions, ctx: &CompletionContext)
 
             let module = match resolution {
                 Some(hir::PathResolution::Def(hir::ModuleDef::Module(it))) => it,
+                Some(hir::PathResolution::ToolModule(tool))
+                    if tool.name(ctx.db) == "clippy"
+                        && annotated_item_kind == Some(SyntaxKind::FN) =>
+                {
+                    lint::complete_clippy_config_attr(acc, ctx);
+                    return;
+                }
                 _ => return,
             };
 
@@ -105,6 +293,11 @@ pub(crate) fn complete_attribute(acc: &mut Completions, ctx: &CompletionContext)
         None => {
             ctx.process_all_names(&mut |name, def| {
                 if let Some(def) = module_or_attr(ctx.db, def) {
+                    if let hir::ScopeDef::ModuleDef(hir::ModuleDef::Macro(mac)) = def {
+                        if !mac.is_builtin_attr(ctx.db) {
+                            attr_macros_in_scope.push(name.to_smol_str());
+                        }
+                    }
                     acc.add_resolution(ctx, name, def);
                 }
             });
@@ -112,6 +305,86 @@ pub(crate) fn complete_attribute(acc: &mut Completions, ctx: &CompletionContext)
         }
     }
 
+    // non-builtin attribute macros in scope, e.g. a custom test framework's `test` from the
+    // prelude, shadow the builtin attributes of the same name
+    complete_builtin_attributes(acc, ctx, is_inner, annotated_item_kind, &attr_macros_in_scope);
+    if !is_inner && could_be_crate_attr(ctx) {
+        complete_as_crate_attributes(acc, ctx, &attr_macros_in_scope);
+    }
+}
+
+/// Whether the outer attribute being completed could as well be meant for the crate, being
+/// the first attribute of the first item in the crate root, e.g. `#[$0]` at the top of `lib.rs`.
+fn could_be_crate_attr(ctx: &CompletionContext) -> bool {
+    let attr = match &ctx.fake_attribute_under_caret {
+        Some(it) if it.kind() == AttrKind::Outer => it,
+        _ => return false,
+    };
+    let item = match attr.syntax().parent() {
+        Some(it) if attr.syntax().prev_sibling().is_none() => it,
+        _ => return false,
+    };
+    item.parent().map_or(false, |it| it.kind() == SyntaxKind::SOURCE_FILE)
+        && item.siblings(Direction::Prev).skip(1).all(|it| it.kind() == SyntaxKind::ATTR)
+        && ctx.module.map_or(false, |it| it.is_crate_root(ctx.db))
+}
+
+/// Adds the crate level attributes at an outer attribute that could be meant for the crate,
+/// labelled `#![…]` and turning the attribute into an inner one when applied.
+fn complete_as_crate_attributes(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    existing: &[SmolStr],
+) {
+    let pound = match ctx.fake_attribute_under_caret.as_ref().and_then(|it| it.pound_token()) {
+        Some(it) => it,
+        None => return,
+    };
+    // the fake file only differs from the original one after the caret
+    let bang_offset = pound.text_range().end();
+    let crate_attributes = KIND_TO_ATTRIBUTES.get(&SyntaxKind::SOURCE_FILE).copied().unwrap_or(&[]);
+
+    for attr_completion in ATTRIBUTES.iter().filter(|it| it.prefer_inner) {
+        let key = attr_completion.key();
+        if !crate_attributes.contains(&key) || existing.iter().any(|it| it == key) {
+            continue;
+        }
+        if attr_completion.feature.map_or(false, |it| !ctx.is_feature_enabled(it)) {
+            continue;
+        }
+        if attr_completion.unique && is_attr_used_elsewhere(ctx, key) {
+            continue;
+        }
+
+        let label = format!("#![{}]", attr_completion.label);
+        let mut item = CompletionItem::new(SymbolKind::Attribute, ctx.source_range(), label);
+        item.lookup_by(key);
+        let mut edit = TextEdit::builder();
+        edit.insert(bang_offset, "!".to_owned());
+        match attr_completion.snippet.zip(ctx.config.snippet_cap) {
+            Some((snippet, cap)) => {
+                edit.replace(ctx.source_range(), snippet.to_owned());
+                item.snippet_edit(cap, edit.finish());
+            }
+            None => {
+                edit.replace(ctx.source_range(), attr_completion.label.to_owned());
+                item.text_edit(edit.finish());
+            }
+        }
+        item.detail("crate attribute");
+        item.add_to(acc);
+    }
+}
+
+/// Adds the builtin attributes applicable to an item of `annotated_item_kind`, or all of
+/// them if it is unknown, except the ones named in `existing`.
+fn complete_builtin_attributes(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    is_inner: bool,
+    annotated_item_kind: Option<SyntaxKind>,
+    existing: &[SmolStr],
+) {
     let attributes = annotated_item_kind.and_then(|kind| {
         if ast::Expr::can_cast(kind) {
             Some(EXPR_ATTRIBUTES)
@@ -120,20 +393,75 @@ pub(crate) fn complete_attribute(acc: &mut Completions, ctx: &CompletionContext)
         }
     });
 
+    let is_leading_crate_attr = is_inner
+        && annotated_item_kind == Some(SyntaxKind::SOURCE_FILE)
+        && ctx.module.map_or(false, |it| it.is_crate_root(ctx.db))
+        && ctx.fake_attribute_under_caret.as_ref().map_or(false, |attr| {
+            attr.syntax().siblings(Direction::Prev).skip(1).all(|it| it.kind() == SyntaxKind::ATTR)
+        });
+
+    let is_proc_macro_crate = ctx.krate.map_or(false, |it| it.is_proc_macro(ctx.db));
+    let is_test_fn = is_test_fn(ctx);
+    let annotated_item_name = annotated_item_name(ctx);
+    let needs_unsafe_wrapper =
+        ctx.krate.map_or(false, |it| it.edition(ctx.db) >= Edition::Edition2024);
+
     let add_completion = |attr_completion: &AttrCompletion| {
-        let mut item =
-            CompletionItem::new(SymbolKind::Attribute, ctx.source_range(), attr_completion.label);
+        if existing.iter().any(|it| it == attr_completion.key()) {
+            return;
+        }
+        if attr_completion.crate_root_only && !is_leading_crate_attr {
+            return;
+        }
+        if attr_completion.proc_macro_crate_only && !is_proc_macro_crate {
+            return;
+        }
+        if attr_completion.test_only && !is_test_fn {
+            return;
+        }
+        if let Some(feature) = attr_completion.feature {
+            if !ctx.is_feature_enabled(feature) {
+                return;
+            }
+        }
+        if attr_completion.unique && is_attr_used_elsewhere(ctx, attr_completion.key()) {
+            return;
+        }
+
+        let wrap_unsafe = attr_completion.is_unsafe && needs_unsafe_wrapper;
+        let mut item = if wrap_unsafe {
+            let label = format!("unsafe({})", attr_completion.label);
+            let mut item = CompletionItem::new(SymbolKind::Attribute, ctx.source_range(), label);
+            item.lookup_by(attr_completion.key());
+            item
+        } else {
+            CompletionItem::new(SymbolKind::Attribute, ctx.source_range(), attr_completion.label)
+        };
 
         if let Some(lookup) = attr_completion.lookup {
             item.lookup_by(lookup);
         }
 
         if let Some((snippet, cap)) = attr_completion.snippet.zip(ctx.config.snippet_cap) {
-            item.insert_snippet(cap, snippet);
+            match (attr_completion.key(), &annotated_item_name) {
+                // renaming the symbol usually starts from the item's own name
+                ("link_name", Some(name)) => {
+                    item.insert_snippet(cap, format!(r#"link_name = "${{0:{}}}""#, name))
+                }
+                _ if wrap_unsafe => item.insert_snippet(cap, format!("unsafe({})", snippet)),
+                _ => item.insert_snippet(cap, snippet),
+            };
+        }
+
+        if let Some(detail) = attr_completion.detail {
+            item.detail(detail);
         }
 
         if is_inner || !attr_completion.prefer_inner {
             item.add_to(acc);
+            if attr_completion.key() == "cfg_attr" {
+                complete_cfg_attr_patterns(acc, ctx);
+            }
         }
     };
 
@@ -143,8 +471,74 @@ pub(crate) fn complete_attribute(acc: &mut Completions, ctx: &CompletionContext)
             .flat_map(|name| ATTRIBUTES.binary_search_by(|attr| attr.key().cmp(name)).ok())
             .flat_map(|idx| ATTRIBUTES.get(idx))
             .for_each(add_completion),
-        None if is_inner => ATTRIBUTES.iter().for_each(add_completion),
-        None => ATTRIBUTES.iter().filter(|compl| !compl.prefer_inner).for_each(add_completion),
+        // the annotated item is unknown, but trait and impl attributes are never the ones
+        // wanted there, as those items are always recognized
+        None if is_inner => ATTRIBUTES
+            .iter()
+            .filter(|compl| !TRAIT_OR_IMPL_ONLY.contains(&compl.key()))
+            .for_each(add_completion),
+        None => ATTRIBUTES
+            .iter()
+            .filter(|compl| !compl.prefer_inner && !TRAIT_OR_IMPL_ONLY.contains(&compl.key()))
+            .for_each(add_completion),
+    }
+}
+
+/// Shortcuts for the most common `cfg_attr` predicates, offered next to the plain `cfg_attr`.
+fn complete_cfg_attr_patterns(acc: &mut Completions, ctx: &CompletionContext) {
+    let cap = match ctx.config.snippet_cap {
+        Some(it) => it,
+        None => return,
+    };
+    let patterns = [
+        (r#"cfg_attr(feature = "…", …)"#, r#"cfg_attr(feature = "$1", $0)"#),
+        ("cfg_attr(test, …)", "cfg_attr(test, $0)"),
+    ];
+    for (label, snippet) in patterns {
+        let mut item = CompletionItem::new(SymbolKind::Attribute, ctx.source_range(), label);
+        item.insert_snippet(cap, snippet);
+        item.add_to(acc);
+    }
+}
+
+/// Whether an attribute named `name` is present in the file, other than the one being completed.
+fn is_attr_used_elsewhere(ctx: &CompletionContext, name: &str) -> bool {
+    let file = match ctx.original_token.ancestors().last() {
+        Some(it) => it,
+        None => return false,
+    };
+    file.descendants()
+        .filter_map(ast::Attr::cast)
+        .filter(|attr| !attr.syntax().text_range().contains_inclusive(ctx.position.offset))
+        .filter_map(|attr| attr.simple_name())
+        .any(|it| it == name)
+}
+
+/// Whether the attribute being completed is on a function that's already marked
+/// as a test, by `#[test]` or a test attribute of some framework, like `#[tokio::test]`.
+fn is_test_fn(ctx: &CompletionContext) -> bool {
+    let attr = match &ctx.fake_attribute_under_caret {
+        Some(it) => it,
+        None => return false,
+    };
+    let func = match attr.syntax().parent().and_then(ast::Fn::cast) {
+        Some(it) => it,
+        None => return false,
+    };
+    func.attrs().filter(|it| it != attr).any(|it| {
+        it.path()
+            .and_then(|path| path.segment()?.name_ref())
+            .map_or(false, |name| name.text() == "test")
+    })
+}
+
+/// The name of the function or static the attribute being completed is on.
+fn annotated_item_name(ctx: &CompletionContext) -> Option<ast::Name> {
+    let item = ctx.fake_attribute_under_caret.as_ref()?.syntax().parent()?;
+    match ast::Item::cast(item)? {
+        ast::Item::Fn(it) => it.name(),
+        ast::Item::Static(it) => it.name(),
+        _ => None,
     }
 }
 
@@ -153,6 +547,19 @@ struct AttrCompletion {
     lookup: Option<&'static str>,
     snippet: Option<&'static str>,
     prefer_inner: bool,
+    /// Only offered in the leading inner attributes of the crate root.
+    crate_root_only: bool,
+    /// The unstable feature that has to be enabled for this attribute to be offered.
+    feature: Option<&'static str>,
+    /// Not offered if the file already uses this attribute elsewhere.
+    unique: bool,
+    /// Only offered in crates with the `proc-macro` crate type.
+    proc_macro_crate_only: bool,
+    /// Only offered on test functions, see [`is_test_fn`].
+    test_only: bool,
+    /// Has to be wrapped in `unsafe(...)` from edition 2024 on.
+    is_unsafe: bool,
+    detail: Option<&'static str>,
 }
 
 impl AttrCompletion {
@@ -163,6 +570,34 @@ impl AttrCompletion {
     const fn prefer_inner(self) -> AttrCompletion {
         AttrCompletion { prefer_inner: true, ..self }
     }
+
+    const fn crate_root_only(self) -> AttrCompletion {
+        AttrCompletion { crate_root_only: true, ..self }
+    }
+
+    const fn feature(self, feature: &'static str) -> AttrCompletion {
+        AttrCompletion { feature: Some(feature), ..self }
+    }
+
+    const fn unique(self) -> AttrCompletion {
+        AttrCompletion { unique: true, ..self }
+    }
+
+    const fn proc_macro_crate_only(self) -> AttrCompletion {
+        AttrCompletion { proc_macro_crate_only: true, ..self }
+    }
+
+    const fn test_only(self) -> AttrCompletion {
+        AttrCompletion { test_only: true, ..self }
+    }
+
+    const fn unsafe_attr(self) -> AttrCompletion {
+        AttrCompletion { is_unsafe: true, ..self }
+    }
+
+    const fn detail(self, detail: &'static str) -> AttrCompletion {
+        AttrCompletion { detail: Some(detail), ..self }
+    }
 }
 
 const fn attr(
@@ -170,7 +605,19 @@ const fn attr(
     lookup: Option<&'static str>,
     snippet: Option<&'static str>,
 ) -> AttrCompletion {
-    AttrCompletion { label, lookup, snippet, prefer_inner: false }
+    AttrCompletion {
+        label,
+        lookup,
+        snippet,
+        prefer_inner: false,
+        crate_root_only: false,
+        feature: None,
+        unique: false,
+        proc_macro_crate_only: false,
+        test_only: false,
+        is_unsafe: false,
+        detail: None,
+    }
 }
 
 macro_rules! attrs {
@@ -201,7 +648,7 @@ macro_rules! attrs {
     [@ {} {$($tt:tt)*}] => { &[$($tt)*] as _ };
     // starting matcher
     [$($tt:tt),*] => {
-        attrs!(@ { $($tt)* } { "allow", "cfg", "cfg_attr", "deny", "forbid", "warn" })
+        attrs!(@ { $($tt)* } { "allow", "cfg", "cfg_attr", "deny", "expect", "forbid", "warn" })
     };
 }
 
@@ -213,11 +660,11 @@ static KIND_TO_ATTRIBUTES: Lazy<FxHashMap<SyntaxKind, &[&str]>> = Lazy::new(|| {
             SOURCE_FILE,
             attrs!(
                 item,
-                "crate_name", "feature", "no_implicit_prelude", "no_main", "no_std",
+                "crate_name", "feature", "no_core", "no_implicit_prelude", "no_main", "no_std",
                 "recursion_limit", "type_length_limit", "windows_subsystem"
             ),
         ),
-        (MODULE, attrs!(item, "macro_use", "no_implicit_prelude", "path")),
+        (MODULE, attrs!(item, "coverage", "macro_use", "no_implicit_prelude", "path")),
         (ITEM_LIST, attrs!(item, "no_implicit_prelude")),
         (MACRO_RULES, attrs!(item, "macro_export", "macro_use")),
         (MACRO_DEF, attrs!(item)),
@@ -232,14 +679,14 @@ static KIND_TO_ATTRIBUTES: Lazy<FxHashMap<SyntaxKind, &[&str]>> = Lazy::new(|| {
             FN,
             attrs!(
                 item, linkable,
-                "cold", "ignore", "inline", "must_use", "panic_handler", "proc_macro",
+                "cold", "coverage", "ignore", "inline", "must_use", "panic_handler", "proc_macro",
                 "proc_macro_derive", "proc_macro_attribute", "should_panic", "target_feature",
                 "test", "track_caller"
             ),
         ),
         (STATIC, attrs!(item, linkable, "global_allocator", "used")),
-        (TRAIT, attrs!(item, "must_use")),
-        (IMPL, attrs!(item, "automatically_derived")),
+        (TRAIT, attrs!(item, "const_trait", "marker", "must_use")),
+        (IMPL, attrs!(item, "automatically_derived", "coverage", "do_not_recommend")),
         (ASSOC_ITEM_LIST, attrs!(item)),
         (EXTERN_BLOCK, attrs!(item, "link")),
         (EXTERN_ITEM_LIST, attrs!(item, "link")),
@@ -250,7 +697,7 @@ static KIND_TO_ATTRIBUTES: Lazy<FxHashMap<SyntaxKind, &[&str]>> = Lazy::new(|| {
         (VARIANT, attrs!("non_exhaustive")),
         (TYPE_PARAM, attrs!()),
         (CONST_PARAM, attrs!()),
-        (LIFETIME_PARAM, attrs!()),
+        (LIFETIME_PARAM, attrs!("may_dangle")),
         (LET_STMT, attrs!()),
         (EXPR_STMT, attrs!()),
         (LITERAL, attrs!()),
@@ -266,6 +713,11 @@ static KIND_TO_ATTRIBUTES: Lazy<FxHashMap<SyntaxKind, &[&str]>> = Lazy::new(|| {
 });
 const EXPR_ATTRIBUTES: &[&str] = attrs!();
 
+/// Attributes that only apply to a trait definition or to an impl, left out when the
+/// annotated item can't be determined.
+const TRAIT_OR_IMPL_ONLY: &[&str] =
+    &["automatically_derived", "const_trait", "do_not_recommend", "marker"];
+
 /// <https://doc.rust-lang.org/reference/attributes.html#built-in-attributes-index>
 // Keep these sorted for the binary search!
 const ATTRIBUTES: &[AttrCompletion] = &[
@@ -274,23 +726,28 @@ const ATTRIBUTES: &[AttrCompletion] = &[
     attr("cfg(…)", Some("cfg"), Some("cfg(${0:predicate})")),
     attr("cfg_attr(…)", Some("cfg_attr"), Some("cfg_attr(${1:predicate}, ${0:attr})")),
     attr("cold", None, None),
+    attr("const_trait", None, None).feature("const_trait_impl"),
+    attr("coverage(…)", Some("coverage"), Some("coverage(${0:off})")).feature("coverage_attribute"),
     attr(r#"crate_name = """#, Some("crate_name"), Some(r#"crate_name = "${0:crate_name}""#))
         .prefer_inner(),
     attr("deny(…)", Some("deny"), Some("deny(${0:lint})")),
     attr(r#"deprecated"#, Some("deprecated"), Some(r#"deprecated"#)),
     attr("derive(…)", Some("derive"), Some(r#"derive(${0:Debug})"#)),
+    attr("do_not_recommend", None, None).feature("do_not_recommend"),
     attr(r#"doc = "…""#, Some("doc"), Some(r#"doc = "${0:docs}""#)),
     attr(r#"doc(alias = "…")"#, Some("docalias"), Some(r#"doc(alias = "${0:docs}")"#)),
     attr(r#"doc(hidden)"#, Some("dochidden"), Some(r#"doc(hidden)"#)),
+    attr("expect(…)", Some("expect"), Some("expect(${0:lint})")),
     attr(
         r#"export_name = "…""#,
         Some("export_name"),
         Some(r#"export_name = "${0:exported_symbol_name}""#),
-    ),
+    )
+    .unsafe_attr(),
     attr("feature(…)", Some("feature"), Some("feature(${0:flag})")).prefer_inner(),
     attr("forbid(…)", Some("forbid"), Some("forbid(${0:lint})")),
     attr("global_allocator", None, None),
-    attr(r#"ignore = "…""#, Some("ignore"), Some(r#"ignore = "${0:reason}""#)),
+    attr(r#"ignore = "…""#, Some("ignore"), Some(r#"ignore = "${0:reason}""#)).test_only(),
     attr("inline", Some("inline"), Some("inline")),
     attr("link", None, None),
     attr(r#"link_name = "…""#, Some("link_name"), Some(r#"link_name = "${0:symbol_name}""#)),
@@ -298,21 +755,26 @@ const ATTRIBUTES: &[AttrCompletion] = &[
         r#"link_section = "…""#,
         Some("link_section"),
         Some(r#"link_section = "${0:section_name}""#),
-    ),
+    )
+    .unsafe_attr(),
     attr("macro_export", None, None),
     attr("macro_use", None, None),
+    attr("marker", None, None).feature("marker_trait_attr"),
+    attr("may_dangle", None, None).feature("dropck_eyepatch"),
     attr(r#"must_use"#, Some("must_use"), Some(r#"must_use"#)),
-    attr("no_implicit_prelude", None, None).prefer_inner(),
+    attr("no_core", None, None).prefer_inner().crate_root_only().feature("no_core"),
+    attr("no_implicit_prelude", None, None),
     attr("no_link", None, None).prefer_inner(),
     attr("no_main", None, None).prefer_inner(),
-    attr("no_mangle", None, None),
-    attr("no_std", None, None).prefer_inner(),
+    attr("no_mangle", None, None).unsafe_attr(),
+    attr("no_std", None, None).prefer_inner().crate_root_only(),
     attr("non_exhaustive", None, None),
-    attr("panic_handler", None, None),
+    attr("panic_handler", None, None).unique().detail("fn(&PanicInfo) -> !"),
     attr(r#"path = "…""#, Some("path"), Some(r#"path ="${0:path}""#)),
-    attr("proc_macro", None, None),
-    attr("proc_macro_attribute", None, None),
-    attr("proc_macro_derive(…)", Some("proc_macro_derive"), Some("proc_macro_derive(${0:Trait})")),
+    attr("proc_macro", None, None).proc_macro_crate_only(),
+    attr("proc_macro_attribute", None, None).proc_macro_crate_only(),
+    attr("proc_macro_derive(…)", Some("proc_macro_derive"), Some("proc_macro_derive(${0:Trait})"))
+        .proc_macro_crate_only(),
     attr(
         r#"recursion_limit = "…""#,
         Some("recursion_limit"),
@@ -320,16 +782,20 @@ const ATTRIBUTES: &[AttrCompletion] = &[
     )
     .prefer_inner(),
     attr("repr(…)", Some("repr"), Some("repr(${0:C})")),
-    attr("should_panic", Some("should_panic"), Some(r#"should_panic"#)),
+    attr("should_panic", Some("should_panic"), Some(r#"should_panic"#)).test_only(),
     attr(
-        r#"target_feature = "…""#,
+        r#"target_feature(enable = "…")"#,
         Some("target_feature"),
-        Some(r#"target_feature = "${0:feature}""#),
+        Some(r#"target_feature(enable = "${0:feature}")"#),
     ),
     attr("test", None, None),
     attr("track_caller", None, None),
-    attr("type_length_limit = …", Some("type_length_limit"), Some("type_length_limit = ${0:128}"))
-        .prefer_inner(),
+    attr(
+        r#"type_length_limit = "…""#,
+        Some("type_length_limit"),
+        Some(r#"type_length_limit = "${0:1048576}""#),
+    )
+    .prefer_inner(),
     attr("used", None, None),
     attr("warn(…)", Some("warn"), Some("warn(${0:lint})")),
     attr(
@@ -341,12 +807,12 @@ const ATTRIBUTES: &[AttrCompletion] = &[
 ];
 
 fn parse_comma_sep_expr(input: ast::TokenTree) -> Option<Vec<ast::Expr>> {
-    let r_paren = input.r_paren_token()?;
-    let tokens = input
-        .syntax()
-        .children_with_tokens()
-        .skip(1)
-        .take_while(|it| it.as_token() != Some(&r_paren));
+    let r_paren = input.r_paren_token();
+    let tokens = input.syntax().children_with_tokens().skip(1).take_while(|it| match it {
+        // an unclosed attribute swallows the item it is attached to, stop at its first keyword
+        NodeOrToken::Token(tok) => !tok.kind().is_keyword() && Some(tok) != r_paren.as_ref(),
+        NodeOrToken::Node(_) => true,
+    });
     let input_expressions = tokens.into_iter().group_by(|tok| tok.kind() == T![,]);
     Some(
         input_expressions
@@ -372,3 +838,15 @@ fn attributes_are_sorted() {
         prev = next;
     });
 }
+
+#[test]
+fn trait_or_impl_only_attributes_are_not_applicable_elsewhere() {
+    for (kind, attrs) in KIND_TO_ATTRIBUTES.iter() {
+        if matches!(kind, SyntaxKind::TRAIT | SyntaxKind::IMPL) {
+            continue;
+        }
+        for attr in TRAIT_OR_IMPL_ONLY {
+            assert!(!attrs.contains(attr), "{} is applicable to {:?}", attr, kind);
+        }
+    }
+}
@@ -1,9 +1,9 @@
 //! Completion for cfg
 
-use std::iter;
+use std::{collections::BTreeMap, iter};
 
 use ide_db::SymbolKind;
-use syntax::SyntaxKind;
+use syntax::{algo::non_trivia_sibling, ast, AstNode, Direction, SmolStr, SyntaxKind, SyntaxToken};
 
 use crate::{completions::Completions, context::CompletionContext, CompletionItem};
 
@@ -14,19 +14,34 @@ pub(crate) fn complete_cfg(acc: &mut Completions, ctx: &CompletionContext) {
         acc.add(completion.build());
     };
 
-    let previous = iter::successors(ctx.original_token.prev_token(), |t| {
-        (matches!(t.kind(), SyntaxKind::EQ) || t.kind().is_trivia())
-            .then(|| t.prev_token())
-            .flatten()
-    })
-    .find(|t| matches!(t.kind(), SyntaxKind::IDENT));
+    // the key of a `key = $0` predicate, the caret might be on a partially typed value
+    let before_value = match ctx.original_token.kind() {
+        SyntaxKind::IDENT | SyntaxKind::STRING => ctx.original_token.prev_token(),
+        _ => Some(ctx.original_token.clone()),
+    };
+    let skip_trivia = |token: Option<SyntaxToken>| {
+        iter::successors(token, |t| t.prev_token()).find(|t| !t.kind().is_trivia())
+    };
+    let previous = skip_trivia(before_value)
+        .filter(|t| t.kind() == SyntaxKind::EQ)
+        .and_then(|eq| skip_trivia(eq.prev_token()))
+        .filter(|t| t.kind() == SyntaxKind::IDENT);
 
     match previous.as_ref().map(|p| p.text()) {
+        Some("target_abi") => KNOWN_ABI.iter().copied().for_each(add_completion),
         Some("target_arch") => KNOWN_ARCH.iter().copied().for_each(add_completion),
         Some("target_env") => KNOWN_ENV.iter().copied().for_each(add_completion),
         Some("target_os") => KNOWN_OS.iter().copied().for_each(add_completion),
         Some("target_vendor") => KNOWN_VENDOR.iter().copied().for_each(add_completion),
         Some("target_endian") => ["little", "big"].into_iter().for_each(add_completion),
+        Some("panic") => ["abort", "unwind"].into_iter().for_each(add_completion),
+        Some("target_pointer_width") => ["16", "32", "64"].into_iter().for_each(add_completion),
+        Some("sanitize") if ctx.is_feature_enabled("cfg_sanitize") => {
+            KNOWN_SANITIZERS.iter().copied().for_each(add_completion)
+        }
+        Some("target_has_atomic") => {
+            ["8", "16", "32", "64", "128", "ptr"].into_iter().for_each(add_completion)
+        }
         Some(name) => {
             if let Some(krate) = ctx.krate {
                 krate.potential_cfg(ctx.db).get_cfg_values(&name).cloned().for_each(|s| {
@@ -40,16 +55,108 @@ pub(crate) fn complete_cfg(acc: &mut Completions, ctx: &CompletionContext) {
             };
         }
         None => {
+            // key -> whether the key takes a value, like `target_os = "..."`
+            let mut keys: BTreeMap<SmolStr, bool> = KNOWN_FLAGS
+                .iter()
+                .map(|&flag| (flag.into(), false))
+                .chain(KNOWN_KEY_VALUES.iter().map(|&key| (key.into(), true)))
+                .collect();
+            if ctx.is_feature_enabled("cfg_sanitize") {
+                keys.insert("sanitize".into(), true);
+            }
             if let Some(krate) = ctx.krate {
-                krate.potential_cfg(ctx.db).get_cfg_keys().cloned().for_each(|s| {
-                    let item = CompletionItem::new(SymbolKind::BuiltinAttr, ctx.source_range(), s);
-                    acc.add(item.build());
-                })
+                for cfg_options in [krate.cfg(ctx.db), krate.potential_cfg(ctx.db)] {
+                    for key in cfg_options.get_cfg_keys() {
+                        let has_value = cfg_options.get_cfg_values(key).next().is_some();
+                        *keys.entry(key.clone()).or_default() |= has_value;
+                    }
+                }
+            }
+            for (key, has_value) in keys {
+                let mut item =
+                    CompletionItem::new(SymbolKind::BuiltinAttr, ctx.source_range(), key.clone());
+                if has_value {
+                    match ctx.config.snippet_cap {
+                        Some(cap) => item.insert_snippet(cap, format!(r#"{} = "$0""#, key)),
+                        None => item.insert_text(format!("{} = ", key)),
+                    };
+                }
+                acc.add(item.build());
             }
+
+            if is_not_operand(&ctx.original_token) {
+                return;
+            }
+            let mut item = CompletionItem::new(
+                SymbolKind::BuiltinAttr,
+                ctx.source_range(),
+                r#"not(feature = "…")"#,
+            );
+            item.lookup_by("not");
+            match ctx.config.snippet_cap {
+                Some(cap) => item.insert_snippet(cap, r#"not(feature = "$0")"#),
+                None => item.insert_text(r#"not(feature = "")"#),
+            };
+            acc.add(item.build());
         }
     };
 }
 
+/// Whether `token` is inside the parentheses of a `not(...)` predicate.
+fn is_not_operand(token: &SyntaxToken) -> bool {
+    token
+        .parent()
+        .and_then(ast::TokenTree::cast)
+        .and_then(|tt| non_trivia_sibling(tt.syntax().clone().into(), Direction::Prev))
+        .and_then(|it| it.into_token())
+        .map_or(false, |it| it.kind() == SyntaxKind::IDENT && it.text() == "not")
+}
+
+const KNOWN_FLAGS: [&str; 9] = [
+    "debug_assertions",
+    "doc",
+    "doctest",
+    "miri",
+    "overflow_checks",
+    "proc_macro",
+    "test",
+    "unix",
+    "windows",
+];
+
+const KNOWN_KEY_VALUES: [&str; 11] = [
+    "feature",
+    "panic",
+    "target_abi",
+    "target_arch",
+    "target_endian",
+    "target_env",
+    "target_family",
+    "target_has_atomic",
+    "target_os",
+    "target_pointer_width",
+    "target_vendor",
+];
+
+/// The values of the unstable `sanitize` cfg, gated by `cfg_sanitize`.
+const KNOWN_SANITIZERS: [&str; 10] = [
+    "address",
+    "cfi",
+    "hwaddress",
+    "kcfi",
+    "leak",
+    "memory",
+    "memtag",
+    "safestack",
+    "shadow-call-stack",
+    "thread",
+];
+
+const KNOWN_ABI: [&str; 13] = [
+    "abi64", "eabi", "eabihf", "elfv1", "elfv2", "fortanix", "ilp32", "llvm", "macabi", "sim",
+    "spe", "uwp", "x32",
+];
+
 const KNOWN_ARCH: [&str; 19] = [
     "aarch64",
     "arm",
@@ -6,25 +6,48 @@ use ide_db::{
 };
 use itertools::Itertools;
 use rustc_hash::FxHashSet;
-use syntax::{ast, SmolStr, SyntaxKind};
+use syntax::{algo::non_trivia_sibling, ast, AstNode, Direction, SmolStr, SyntaxKind};
 
 use crate::{
     completions::flyimport::compute_fuzzy_completion_order_key, context::CompletionContext,
     item::CompletionItem, Completions, ImportEdit,
 };
 
-pub(super) fn complete_derive(acc: &mut Completions, ctx: &CompletionContext, attr: &ast::Attr) {
+pub(super) fn complete_derive(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    attr: &ast::Attr,
+    existing: &[ast::Path],
+) {
     let core = ctx.famous_defs().core();
-    let existing_derives: FxHashSet<_> =
-        ctx.sema.resolve_derive_macro(attr).into_iter().flatten().flatten().collect();
+    let derives_in_scope = get_derives_in_scope(ctx);
+    let on_union = attr.syntax().parent().map_or(false, |it| it.kind() == SyntaxKind::UNION);
+    let existing_derives: FxHashSet<_> = match ctx.sema.resolve_derive_macro(attr) {
+        Some(it) => it.into_iter().flatten().collect(),
+        // a `derive` nested in `cfg_attr` isn't resolved as such, match the names instead
+        None => derives_in_scope
+            .iter()
+            .filter(|(name, _)| {
+                existing
+                    .iter()
+                    .filter_map(|path| path.as_single_name_ref())
+                    .any(|it| name.to_smol_str() == it.text().as_str())
+            })
+            .map(|&(_, mac)| mac)
+            .collect(),
+    };
 
-    for (name, mac) in get_derives_in_scope(ctx) {
+    for (name, mac) in derives_in_scope {
         if existing_derives.contains(&mac) {
             continue;
         }
 
         let name = name.to_smol_str();
-        let (label, lookup) = match (core, mac.module(ctx.db).krate()) {
+        let mac_krate = mac.module(ctx.db).krate();
+        if on_union && core == Some(mac_krate) && !UNION_DERIVES.contains(&name.as_str()) {
+            continue;
+        }
+        let (label, lookup) = match (core, mac_krate) {
             // show derive dependencies for `core`/`std` derives
             (Some(core), mac_krate) if core == mac_krate => {
                 if let Some(derive_completion) = DEFAULT_DERIVE_DEPENDENCIES
@@ -51,7 +74,8 @@ pub(super) fn complete_derive(acc: &mut Completions, ctx: &CompletionContext, at
         };
 
         let mut item = CompletionItem::new(SymbolKind::Derive, ctx.source_range(), label);
-        if let Some(docs) = mac.docs(ctx.db) {
+        item.detail(derive_detail(ctx, mac));
+        if let Some(docs) = derive_docs(ctx, mac) {
             item.documentation(docs);
         }
         if let Some(lookup) = lookup {
@@ -63,6 +87,32 @@ pub(super) fn complete_derive(acc: &mut Completions, ctx: &CompletionContext, at
     flyimport_derive(acc, ctx);
 }
 
+/// Completes the helper attributes of the derive whose arguments, as in
+/// `derive(Foo($0))`, are given in `args`.
+pub(super) fn complete_derive_args(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    args: &ast::TokenTree,
+) {
+    let name = match non_trivia_sibling(args.syntax().clone().into(), Direction::Prev)
+        .and_then(|it| it.into_token())
+    {
+        Some(it) if it.kind() == SyntaxKind::IDENT => it,
+        _ => return,
+    };
+    let mac =
+        match get_derives_in_scope(ctx).into_iter().find(|(it, _)| it.to_smol_str() == name.text())
+        {
+            Some((_, mac)) => mac,
+            None => return,
+        };
+    for helper in mac.derive_helpers(ctx.db) {
+        let item =
+            CompletionItem::new(SymbolKind::Attribute, ctx.source_range(), helper.to_smol_str());
+        item.add_to(acc);
+    }
+}
+
 fn get_derives_in_scope(ctx: &CompletionContext) -> Vec<(hir::Name, Macro)> {
     let mut result = Vec::default();
     ctx.process_all_names(&mut |name, scope_def| {
@@ -111,7 +161,8 @@ fn flyimport_derive(acc: &mut Completions, ctx: &CompletionContext) -> Option<()
                     mac.name(ctx.db).to_smol_str(),
                 );
                 item.add_import(ImportEdit { import, scope: import_scope.clone() });
-                if let Some(docs) = mac.docs(ctx.db) {
+                item.detail(derive_detail(ctx, mac));
+                if let Some(docs) = derive_docs(ctx, mac) {
                     item.documentation(docs);
                 }
                 Some(item.build())
@@ -120,11 +171,68 @@ fn flyimport_derive(acc: &mut Completions, ctx: &CompletionContext) -> Option<()
     Some(())
 }
 
+/// Tells builtin derives, along with what they generate, apart from proc-macro ones.
+fn derive_detail(ctx: &CompletionContext, mac: Macro) -> String {
+    if !mac.is_builtin_derive(ctx.db) {
+        return "proc-macro derive".to_owned();
+    }
+    match builtin_derive_info(ctx, mac) {
+        Some(info) => format!("builtin derive, {}", info.note),
+        None => "builtin derive".to_owned(),
+    }
+}
+
+/// The macro's docs, preceded by the trait it implements for builtin derives.
+fn derive_docs(ctx: &CompletionContext, mac: Macro) -> Option<hir::Documentation> {
+    let docs = mac.docs(ctx.db);
+    let info = match builtin_derive_info(ctx, mac) {
+        Some(it) => it,
+        None => return docs,
+    };
+    let mut text = format!("implements `{}`", info.trait_path);
+    if let Some(docs) = docs {
+        text.push_str("\n\n");
+        text.push_str(docs.as_str());
+    }
+    Some(hir::Documentation::new(text))
+}
+
+fn builtin_derive_info(ctx: &CompletionContext, mac: Macro) -> Option<&'static BuiltinDerive> {
+    if !mac.is_builtin_derive(ctx.db) {
+        return None;
+    }
+    let name = mac.name(ctx.db).to_smol_str();
+    BUILTIN_DERIVES.iter().find(|it| it.label == name)
+}
+
+struct BuiltinDerive {
+    label: &'static str,
+    trait_path: &'static str,
+    note: &'static str,
+}
+
+#[rustfmt::skip]
+const BUILTIN_DERIVES: &[BuiltinDerive] = &[
+    BuiltinDerive { label: "Clone",      trait_path: "core::clone::Clone",      note: "clones each field" },
+    BuiltinDerive { label: "Copy",       trait_path: "core::marker::Copy",      note: "copies the value bitwise" },
+    BuiltinDerive { label: "Debug",      trait_path: "core::fmt::Debug",        note: "formats each field with `{:?}`" },
+    BuiltinDerive { label: "Default",    trait_path: "core::default::Default",  note: "defaults each field" },
+    BuiltinDerive { label: "Eq",         trait_path: "core::cmp::Eq",           note: "marks `==` as an equivalence" },
+    BuiltinDerive { label: "Hash",       trait_path: "core::hash::Hash",        note: "hashes each field" },
+    BuiltinDerive { label: "Ord",        trait_path: "core::cmp::Ord",          note: "orders fields lexicographically" },
+    BuiltinDerive { label: "PartialEq",  trait_path: "core::cmp::PartialEq",    note: "compares each field" },
+    BuiltinDerive { label: "PartialOrd", trait_path: "core::cmp::PartialOrd",   note: "orders fields lexicographically" },
+];
+
 struct DeriveDependencies {
     label: &'static str,
     dependencies: &'static [&'static str],
 }
 
+/// The builtin derives that work on unions, the others need to look at the
+/// fields, which can't be done without knowing the active one.
+const UNION_DERIVES: &[&str] = &["Clone", "Copy"];
+
 /// Standard Rust derives that have dependencies
 /// (the dependencies are needed so that the main derive don't break the compilation when added)
 const DEFAULT_DERIVE_DEPENDENCIES: &[DeriveDependencies] = &[
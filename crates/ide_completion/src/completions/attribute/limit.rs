@@ -0,0 +1,38 @@
+//! Completion for the values of `#![recursion_limit = "…"]` and `#![type_length_limit = "…"]`.
+use ide_db::SymbolKind;
+use syntax::{ast, AstToken, SyntaxKind};
+
+use crate::{context::CompletionContext, item::CompletionItem, Completions};
+
+/// Both limits take an integer in a string literal, so besides offering the
+/// default value this suggests quoting an integer that was written bare.
+pub(super) fn complete_limit(acc: &mut Completions, ctx: &CompletionContext, name: &str) {
+    let default = match name {
+        "recursion_limit" => "128",
+        "type_length_limit" => "1048576",
+        _ => return,
+    };
+    let token = &ctx.original_token;
+    let (range, detail, value) = match token.kind() {
+        SyntaxKind::INT_NUMBER => {
+            (token.text_range(), "the limit has to be a quoted integer", token.text())
+        }
+        SyntaxKind::STRING => {
+            let is_integer = ast::String::cast(token.clone())
+                .and_then(|it| it.value().map(|it| it.parse::<u64>().is_ok()))
+                .unwrap_or(false);
+            if is_integer {
+                return;
+            }
+            (token.text_range(), "the limit has to be an integer", default)
+        }
+        _ => (ctx.source_range(), "the default limit", default),
+    };
+
+    let mut item = CompletionItem::new(SymbolKind::BuiltinAttr, range, format!(r#""{}""#, value));
+    item.detail(detail);
+    if let Some(cap) = ctx.config.snippet_cap {
+        item.insert_snippet(cap, format!(r#""${{0:{}}}""#, value));
+    }
+    item.add_to(acc);
+}
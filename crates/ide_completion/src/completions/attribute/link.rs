@@ -0,0 +1,123 @@
+//! Completion for `#[link(...)]` on extern blocks.
+use std::iter;
+
+use ide_db::SymbolKind;
+use syntax::{ast, AstNode, SyntaxKind, SyntaxToken, TextRange, TextSize, T};
+
+use crate::{context::CompletionContext, item::CompletionItem, Completions};
+
+const KEYS: [&str; 5] = ["name", "kind", "modifiers", "wasm_import_module", "import_name_type"];
+
+const KINDS: [&str; 4] = ["dylib", "framework", "raw-dylib", "static"];
+
+const IMPORT_NAME_TYPES: [&str; 3] = ["decorated", "noprefix", "undecorated"];
+
+/// Offers the keys not yet given to `#[link(...)]`, or the values of `kind` and
+/// `import_name_type`.
+///
+/// `import_name_type` only applies to `kind = "raw-dylib"` links of Windows MSVC
+/// targets, so it is left out everywhere else.
+pub(super) fn complete_link(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    input: &ast::TokenTree,
+) {
+    let existing = existing_keys(input);
+    let import_name_type_applicable = is_windows_msvc(ctx)
+        && existing.iter().any(|(key, value)| {
+            key.text() == "kind" && value.as_ref().map_or(false, |it| it.text() == r#""raw-dylib""#)
+        });
+
+    let token = &ctx.original_token;
+    let in_string = token.kind() == SyntaxKind::STRING;
+    let value_of = match key_of_value(token, in_string) {
+        Some(key) => key,
+        None => {
+            for key in KEYS {
+                let already_given = existing.iter().any(|(it, _)| it.text() == key);
+                if already_given || (key == "import_name_type" && !import_name_type_applicable) {
+                    continue;
+                }
+                let label = format!(r#"{} = "…""#, key);
+                let mut item =
+                    CompletionItem::new(SymbolKind::BuiltinAttr, ctx.source_range(), label);
+                item.lookup_by(key);
+                match ctx.config.snippet_cap {
+                    Some(cap) => item.insert_snippet(cap, format!(r#"{} = "$0""#, key)),
+                    None => item.insert_text(format!(r#"{} = """#, key)),
+                };
+                item.add_to(acc);
+            }
+            return;
+        }
+    };
+
+    let values: &[&str] = match value_of.text() {
+        "kind" => &KINDS,
+        "import_name_type" if import_name_type_applicable => &IMPORT_NAME_TYPES,
+        _ => return,
+    };
+    // inside the string only the text typed so far is replaced
+    let range = if in_string {
+        let start = token.text_range().start() + TextSize::of('"');
+        if ctx.position.offset < start {
+            return;
+        }
+        TextRange::new(start, ctx.position.offset)
+    } else {
+        ctx.source_range()
+    };
+    for &value in values {
+        let mut item = CompletionItem::new(SymbolKind::BuiltinAttr, range, value);
+        if !in_string {
+            item.insert_text(format!(r#""{}""#, value));
+        }
+        item.add_to(acc);
+    }
+}
+
+/// The key of the `key = $0` value the caret is on, if any.
+fn key_of_value(token: &SyntaxToken, in_string: bool) -> Option<SyntaxToken> {
+    let before_value = if in_string || token.kind() == SyntaxKind::IDENT {
+        token.prev_token()
+    } else {
+        Some(token.clone())
+    };
+    skip_trivia(before_value)
+        .filter(|t| t.kind() == T![=])
+        .and_then(|eq| skip_trivia(eq.prev_token()))
+        .filter(|t| t.kind() == SyntaxKind::IDENT)
+}
+
+/// The `key = value` pairs given in `input`, the value being `None` if it
+/// isn't there yet.
+fn existing_keys(input: &ast::TokenTree) -> Vec<(SyntaxToken, Option<SyntaxToken>)> {
+    input
+        .syntax()
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| it.kind() == SyntaxKind::IDENT)
+        .filter_map(|key| {
+            let eq = skip_trivia_forward(key.next_token()).filter(|t| t.kind() == T![=])?;
+            let value =
+                skip_trivia_forward(eq.next_token()).filter(|t| t.kind() == SyntaxKind::STRING);
+            Some((key, value))
+        })
+        .collect()
+}
+
+fn is_windows_msvc(ctx: &CompletionContext) -> bool {
+    ctx.krate.map_or(false, |krate| {
+        let cfg = krate.cfg(ctx.db);
+        let is = |key, value: &str| cfg.get_cfg_values(key).any(|it| it == value);
+        is("target_os", "windows") && is("target_env", "msvc")
+    })
+}
+
+fn skip_trivia(token: Option<SyntaxToken>) -> Option<SyntaxToken> {
+    iter::successors(token, |t| t.prev_token()).find(|t| !t.kind().is_trivia())
+}
+
+fn skip_trivia_forward(token: Option<SyntaxToken>) -> Option<SyntaxToken> {
+    iter::successors(token, |t| t.next_token()).find(|t| !t.kind().is_trivia())
+}
@@ -1,6 +1,10 @@
 //! Completion for lints
-use ide_db::{generated::lints::Lint, SymbolKind};
-use syntax::{ast, T};
+use ide_db::{
+    generated::lints::{Lint, CLIPPY_LINTS, FEATURES},
+    helpers::lint_eq_or_in_group,
+    SymbolKind,
+};
+use syntax::{ast, AstNode, SyntaxKind, T};
 
 use crate::{context::CompletionContext, item::CompletionItem, Completions};
 
@@ -9,9 +13,62 @@ pub(super) fn complete_lint(
     ctx: &CompletionContext,
     existing_lints: &[ast::Path],
     lints_completions: &[Lint],
+) {
+    let existing: Vec<String> =
+        existing_lints.iter().map(|it| it.syntax().text().to_string()).collect();
+    let lints = lints_completions
+        .iter()
+        // the lints of a listed group already get the same level
+        .filter(|lint| !existing.iter().any(|group| is_covered_by(lint.label, group)))
+        .map(|it| (it.label, Some(it.description)));
+    // `warnings` sets the level of every lint that would otherwise warn, so
+    // listing any of them as well is most likely redundant
+    let detail = existing.iter().any(|it| it == "warnings").then(|| "covered by `warnings`");
+    complete_lint_names(acc, ctx, existing_lints, lints, detail);
+}
+
+/// The groups `clippy::all` consists of, it isn't part of the generated groups.
+const CLIPPY_ALL: &[&str] = &[
+    "clippy::complexity",
+    "clippy::correctness",
+    "clippy::perf",
+    "clippy::style",
+    "clippy::suspicious",
+];
+
+fn is_covered_by(lint: &str, group: &str) -> bool {
+    if group == "clippy::all" {
+        return CLIPPY_ALL.iter().any(|group| lint_eq_or_in_group(lint, group));
+    }
+    lint_eq_or_in_group(lint, group)
+}
+
+/// Completes the `FEATURES` we know about, as well as the feature names the
+/// user configured for their toolchain, which may be more up to date.
+pub(super) fn complete_feature(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    existing_features: &[ast::Path],
+) {
+    let known = FEATURES.iter().map(|it| (it.label, Some(it.description)));
+    let from_toolchain = ctx
+        .config
+        .toolchain_features
+        .iter()
+        .filter(|name| !FEATURES.iter().any(|it| it.label == name.as_str()))
+        .map(|name| (name.as_str(), None));
+    complete_lint_names(acc, ctx, existing_features, known.chain(from_toolchain), None);
+}
+
+fn complete_lint_names<'a>(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    existing_lints: &[ast::Path],
+    lints: impl Iterator<Item = (&'a str, Option<&'a str>)>,
+    detail: Option<&str>,
 ) {
     let is_qualified = ctx.previous_token_is(T![:]);
-    for &Lint { label, description } in lints_completions {
+    for (label, description) in lints {
         let (qual, name) = {
             // FIXME: change `Lint`'s label to not store a path in it but split the prefix off instead?
             let mut parts = label.split("::");
@@ -55,7 +112,61 @@ pub(super) fn complete_lint(
             _ => name.to_owned(),
         };
         let mut item = CompletionItem::new(SymbolKind::Attribute, ctx.source_range(), label);
-        item.documentation(hir::Documentation::new(description.to_owned()));
+        if let Some(description) = description {
+            item.documentation(hir::Documentation::new(description.to_owned()));
+        }
+        if let Some(detail) = detail {
+            item.detail(detail);
+        }
         item.add_to(acc)
     }
 }
+
+/// Offers the `reason = "…"` that explains why a lint level was set, unless
+/// the attribute already has one.
+pub(super) fn complete_lint_reason(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    input: &ast::TokenTree,
+) {
+    if ctx.previous_token_is(T![:]) {
+        return;
+    }
+    let has_reason = input
+        .syntax()
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .any(|it| it.kind() == SyntaxKind::IDENT && it.text() == "reason");
+    if has_reason {
+        return;
+    }
+    let mut item =
+        CompletionItem::new(SymbolKind::Attribute, ctx.source_range(), r#"reason = "…""#);
+    item.lookup_by("reason");
+    if let Some(cap) = ctx.config.snippet_cap {
+        item.insert_snippet(cap, r#"reason = "$0""#);
+    }
+    item.add_to(acc);
+}
+
+/// Completes clippy's configuration attributes, like `#[clippy::cognitive_complexity = "25"]`,
+/// after a `clippy::` qualifier. These are documented by the lint they configure.
+pub(super) fn complete_clippy_config_attr(acc: &mut Completions, ctx: &CompletionContext) {
+    for &(name, default) in CLIPPY_CONFIG_ATTRIBUTES {
+        let label = format!(r#"{} = "…""#, name);
+        let mut item = CompletionItem::new(SymbolKind::Attribute, ctx.source_range(), label);
+        item.lookup_by(name);
+        if let Some(cap) = ctx.config.snippet_cap {
+            item.insert_snippet(cap, format!(r#"{} = "${{0:{}}}""#, name, default));
+        }
+        let lint = CLIPPY_LINTS.iter().find(|it| it.label.strip_prefix("clippy::") == Some(name));
+        if let Some(lint) = lint {
+            item.documentation(hir::Documentation::new(lint.description.to_owned()));
+        }
+        item.add_to(acc);
+    }
+}
+
+/// The lint thresholds clippy lets functions override, with their default values. The
+/// other thresholds, like `too_many_arguments`, can only be set in `clippy.toml`.
+const CLIPPY_CONFIG_ATTRIBUTES: &[(&str, &str)] = &[("cognitive_complexity", "25")];
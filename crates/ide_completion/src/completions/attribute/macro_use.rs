@@ -0,0 +1,45 @@
+//! Completion for macros imported by `#[macro_use(...)]` on an `extern crate`.
+use hir::HasAttrs;
+use ide_db::SymbolKind;
+use syntax::{ast, AstNode};
+
+use crate::{context::CompletionContext, item::CompletionItem, Completions};
+
+pub(super) fn complete_macro_use(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    existing_imports: &[ast::Path],
+) {
+    let extern_crate = match ctx.attr.as_ref().and_then(|it| it.syntax().parent()) {
+        Some(it) => match ast::ExternCrate::cast(it) {
+            Some(it) => it,
+            None => return,
+        },
+        None => return,
+    };
+    let krate = match ctx.sema.resolve_extern_crate(&extern_crate) {
+        Some(it) => it,
+        None => return,
+    };
+
+    for (name, def) in krate.root_module(ctx.db).scope(ctx.db, None) {
+        let mac = match def {
+            hir::ScopeDef::ModuleDef(hir::ModuleDef::Macro(mac)) if mac.is_fn_like(ctx.db) => mac,
+            _ => continue,
+        };
+        let name = name.to_smol_str();
+        let already_imported = existing_imports
+            .iter()
+            .filter_map(|path| path.as_single_name_ref())
+            .any(|it| it.text() == name.as_str());
+        if already_imported {
+            continue;
+        }
+
+        let mut item = CompletionItem::new(SymbolKind::Macro, ctx.source_range(), name);
+        if let Some(docs) = mac.docs(ctx.db) {
+            item.documentation(docs);
+        }
+        item.add_to(acc);
+    }
+}
@@ -1,27 +1,44 @@
 //! Completion for representations.
 
 use ide_db::SymbolKind;
-use syntax::ast;
+use syntax::{
+    ast::{self, HasGenericParams},
+    AstNode,
+};
 
 use crate::{context::CompletionContext, item::CompletionItem, Completions};
 
 pub(super) fn complete_repr(acc: &mut Completions, ctx: &CompletionContext, input: ast::TokenTree) {
     if let Some(existing_reprs) = super::parse_comma_sep_expr(input) {
-        for &ReprCompletion { label, snippet, lookup, collides } in REPR_COMPLETIONS {
-            let repr_already_annotated = existing_reprs
-                .iter()
-                .filter_map(|expr| match expr {
+        let adt =
+            ctx.attr.as_ref().and_then(|attr| attr.syntax().parent()).and_then(ast::Adt::cast);
+        let transparent_applicable = adt.as_ref().map_or(true, may_be_transparent);
+        let is_union = matches!(adt, Some(ast::Adt::Union(_)));
+        let existing_names: Vec<_> = existing_reprs
+            .iter()
+            .filter_map(|expr| match expr {
+                ast::Expr::PathExpr(path) => path.path()?.as_single_name_ref(),
+                ast::Expr::CallExpr(call) => match call.expr()? {
                     ast::Expr::PathExpr(path) => path.path()?.as_single_name_ref(),
-                    ast::Expr::CallExpr(call) => match call.expr()? {
-                        ast::Expr::PathExpr(path) => path.path()?.as_single_name_ref(),
-                        _ => return None,
-                    },
-                    _ => None,
-                })
-                .any(|it| {
-                    let text = it.text();
-                    lookup.unwrap_or(label) == text || collides.contains(&text.as_str())
-                });
+                    _ => return None,
+                },
+                _ => None,
+            })
+            .map(|it| it.text().to_string())
+            .collect();
+        for &ReprCompletion { label, snippet, lookup, detail, collides } in REPR_COMPLETIONS {
+            if label == "transparent" && !transparent_applicable {
+                continue;
+            }
+            if is_union && DISCRIMINANT_REPRS.contains(&label) {
+                continue;
+            }
+            let repr_already_annotated = existing_names.iter().any(|existing| {
+                let existing = existing.as_str();
+                lookup.unwrap_or(label) == existing
+                    || collides.contains(&existing)
+                    || collisions_of(existing).contains(&lookup.unwrap_or(label))
+            });
             if repr_already_annotated {
                 continue;
             }
@@ -33,38 +50,106 @@ pub(super) fn complete_repr(acc: &mut Completions, ctx: &CompletionContext, inpu
             if let Some((snippet, cap)) = snippet.zip(ctx.config.snippet_cap) {
                 item.insert_snippet(cap, snippet);
             }
+            if let Some(detail) = detail {
+                item.detail(detail);
+            }
             item.add_to(acc);
         }
     }
 }
 
+/// `repr(transparent)` requires exactly one non-zero-sized field, so we only
+/// rule it out if the type clearly has more than that.
+fn may_be_transparent(adt: &ast::Adt) -> bool {
+    let field_list = match adt {
+        ast::Adt::Struct(it) => it.field_list(),
+        ast::Adt::Enum(it) => {
+            let mut variants = match it.variant_list() {
+                Some(it) => it.variants(),
+                None => return true,
+            };
+            match (variants.next(), variants.next()) {
+                (Some(variant), None) => variant.field_list(),
+                (None, _) => return true,
+                (Some(_), Some(_)) => return false,
+            }
+        }
+        ast::Adt::Union(_) => return true,
+    };
+    let field_types: Vec<_> = match field_list {
+        Some(ast::FieldList::RecordFieldList(it)) => it.fields().map(|it| it.ty()).collect(),
+        Some(ast::FieldList::TupleFieldList(it)) => it.fields().map(|it| it.ty()).collect(),
+        None => return true,
+    };
+    if field_types.len() <= 1 {
+        return true;
+    }
+
+    let generic_params: Vec<_> = adt
+        .generic_param_list()
+        .into_iter()
+        .flat_map(|it| it.type_or_const_params())
+        .filter_map(|it| it.name())
+        .collect();
+    // any of the fields could be zero-sized, in which case the layout is ambiguous
+    field_types.iter().any(|ty| match ty {
+        Some(ast::Type::PathType(ty)) => {
+            ty.path().and_then(|path| path.segment()?.name_ref()).map_or(true, |name| {
+                name.text() == "PhantomData"
+                    || generic_params.iter().any(|param| param.text() == name.text())
+            })
+        }
+        Some(ast::Type::TupleType(ty)) => ty.fields().next().is_none(),
+        Some(_) => false,
+        None => true,
+    })
+}
+
+/// The reprs `existing` can't be combined with, so conflicts are caught no
+/// matter which of the two was written first.
+fn collisions_of(existing: &str) -> &'static [&'static str] {
+    REPR_COMPLETIONS
+        .iter()
+        .find(|it| it.lookup.unwrap_or(it.label) == existing)
+        .map_or(&[], |it| it.collides)
+}
+
+/// The reprs choosing the type of an enum's discriminant, unions don't have one.
+const DISCRIMINANT_REPRS: &[&str] =
+    &["u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"];
+
 struct ReprCompletion {
     label: &'static str,
     snippet: Option<&'static str>,
     lookup: Option<&'static str>,
+    detail: Option<&'static str>,
     collides: &'static [&'static str],
 }
 
-const fn attr(label: &'static str, collides: &'static [&'static str]) -> ReprCompletion {
-    ReprCompletion { label, snippet: None, lookup: None, collides }
+const fn attr(
+    label: &'static str,
+    detail: &'static str,
+    collides: &'static [&'static str],
+) -> ReprCompletion {
+    ReprCompletion { label, snippet: None, lookup: None, detail: Some(detail), collides }
 }
 
 #[rustfmt::skip]
 const REPR_COMPLETIONS: &[ReprCompletion] = &[
-    ReprCompletion { label: "align($0)", snippet: Some("align($0)"), lookup: Some("align"), collides: &["transparent", "packed"] },
-    attr("packed", &["transparent", "align"]),
-    attr("transparent", &["C", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"]),
-    attr("C", &["transparent"]),
-    attr("u8",     &["transparent", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"]),
-    attr("u16",    &["transparent", "u8", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"]),
-    attr("u32",    &["transparent", "u8", "u16", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"]),
-    attr("u64",    &["transparent", "u8", "u16", "u32", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"]),
-    attr("u128",   &["transparent", "u8", "u16", "u32", "u64", "usize", "i8", "i16", "i32", "i64", "i128", "isize"]),
-    attr("usize",  &["transparent", "u8", "u16", "u32", "u64", "u128", "i8", "i16", "i32", "i64", "i128", "isize"]),
-    attr("i8",     &["transparent", "u8", "u16", "u32", "u64", "u128", "usize", "i16", "i32", "i64", "i128", "isize"]),
-    attr("i16",    &["transparent", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i32", "i64", "i128", "isize"]),
-    attr("i32",    &["transparent", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i64", "i128", "isize"]),
-    attr("i64",    &["transparent", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i128", "isize"]),
-    attr("i28",    &["transparent", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "isize"]),
-    attr("isize",  &["transparent", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128"]),
+    ReprCompletion { label: "align(…)", snippet: Some("align(${0:8})"), lookup: Some("align"), detail: Some("alignment must be a power of two"), collides: &["transparent", "packed"] },
+    attr("packed", "no padding, fields may be unaligned", &["transparent", "align"]),
+    attr("transparent", "same layout as its single non-zero-sized field", &["C", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"]),
+    attr("C", "C-compatible layout, fields keep their declaration order", &["transparent"]),
+    attr("u8",    "enum discriminant is a `u8`",     &["transparent", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"]),
+    attr("u16",   "enum discriminant is a `u16`",    &["transparent", "u8", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"]),
+    attr("u32",   "enum discriminant is a `u32`",    &["transparent", "u8", "u16", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"]),
+    attr("u64",   "enum discriminant is a `u64`",    &["transparent", "u8", "u16", "u32", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"]),
+    attr("u128",  "enum discriminant is a `u128`",   &["transparent", "u8", "u16", "u32", "u64", "usize", "i8", "i16", "i32", "i64", "i128", "isize"]),
+    attr("usize", "enum discriminant is a `usize`",  &["transparent", "u8", "u16", "u32", "u64", "u128", "i8", "i16", "i32", "i64", "i128", "isize"]),
+    attr("i8",    "enum discriminant is an `i8`",    &["transparent", "u8", "u16", "u32", "u64", "u128", "usize", "i16", "i32", "i64", "i128", "isize"]),
+    attr("i16",   "enum discriminant is an `i16`",   &["transparent", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i32", "i64", "i128", "isize"]),
+    attr("i32",   "enum discriminant is an `i32`",   &["transparent", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i64", "i128", "isize"]),
+    attr("i64",   "enum discriminant is an `i64`",   &["transparent", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i128", "isize"]),
+    attr("i128",  "enum discriminant is an `i128`",  &["transparent", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "isize"]),
+    attr("isize", "enum discriminant is an `isize`", &["transparent", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128"]),
 ];
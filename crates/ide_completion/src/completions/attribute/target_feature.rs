@@ -0,0 +1,118 @@
+//! Completion for `#[target_feature(enable = "…")]`.
+use std::iter;
+
+use ide_db::SymbolKind;
+use syntax::{SyntaxKind, SyntaxToken, TextRange, TextSize};
+
+use crate::{context::CompletionContext, item::CompletionItem, Completions};
+
+const ENABLE_DOCS: &str = "Enables the comma separated target features for this function.
+
+`enable` is the only key rustc accepts, features can't be disabled per function. \
+Feature names are written bare, the `+feature`/`-feature` forms are only understood \
+by `-C target-feature`.";
+
+/// Offers the `enable` key, or the features of the crate's target architecture
+/// as its value. Nothing is offered for targets without a known feature list.
+pub(super) fn complete_target_feature(acc: &mut Completions, ctx: &CompletionContext) {
+    let token = &ctx.original_token;
+    let in_string = token.kind() == SyntaxKind::STRING;
+    let before_value = if in_string { token.prev_token() } else { Some(token.clone()) };
+    let skip_trivia = |token: Option<SyntaxToken>| {
+        iter::successors(token, |t| t.prev_token()).find(|t| !t.kind().is_trivia())
+    };
+    let is_enable_value = skip_trivia(before_value)
+        .filter(|t| t.kind() == SyntaxKind::EQ)
+        .and_then(|eq| skip_trivia(eq.prev_token()))
+        .map_or(false, |t| t.kind() == SyntaxKind::IDENT && t.text() == "enable");
+
+    if !is_enable_value {
+        let mut item =
+            CompletionItem::new(SymbolKind::BuiltinAttr, ctx.source_range(), r#"enable = "…""#);
+        item.lookup_by("enable");
+        item.documentation(hir::Documentation::new(ENABLE_DOCS.to_owned()));
+        match ctx.config.snippet_cap {
+            Some(cap) => item.insert_snippet(cap, r#"enable = "$0""#),
+            None => item.insert_text(r#"enable = """#),
+        };
+        item.add_to(acc);
+        return;
+    }
+
+    let features = match ctx.krate.and_then(|krate| {
+        let cfg = krate.cfg(ctx.db);
+        let arch = cfg.get_cfg_values("target_arch").next()?;
+        features_of(arch)
+    }) {
+        Some(it) => it,
+        None => return,
+    };
+
+    // inside the string only the feature under the caret is replaced, dropping a `+`/`-` sign
+    let (range, sign) = if in_string {
+        let token_start = token.text_range().start();
+        let typed = match token.text().get(1..usize::from(ctx.position.offset - token_start)) {
+            Some(it) if !it.contains('"') => it,
+            _ => return,
+        };
+        let current = typed.rsplit(',').next().unwrap_or_default().trim_start();
+        let sign = current.chars().next().filter(|c| matches!(c, '+' | '-'));
+        (TextRange::new(ctx.position.offset - TextSize::of(current), ctx.position.offset), sign)
+    } else {
+        (ctx.source_range(), None)
+    };
+
+    for &feature in features {
+        let mut item = CompletionItem::new(SymbolKind::BuiltinAttr, range, feature);
+        if in_string {
+            if let Some(sign) = sign {
+                item.lookup_by(format!("{}{}", sign, feature));
+            }
+        } else {
+            item.insert_text(format!(r#""{}""#, feature));
+        }
+        item.add_to(acc);
+    }
+}
+
+fn features_of(arch: &str) -> Option<&'static [&'static str]> {
+    let features: &[&str] = match arch {
+        "x86" | "x86_64" => &X86_FEATURES,
+        "aarch64" => &AARCH64_FEATURES,
+        "wasm32" | "wasm64" => &["simd128"],
+        _ => return None,
+    };
+    Some(features)
+}
+
+const X86_FEATURES: [&str; 24] = [
+    "adx",
+    "aes",
+    "avx",
+    "avx2",
+    "bmi1",
+    "bmi2",
+    "fma",
+    "fxsr",
+    "lzcnt",
+    "pclmulqdq",
+    "popcnt",
+    "rdrand",
+    "rdseed",
+    "sha",
+    "sse",
+    "sse2",
+    "sse3",
+    "sse4.1",
+    "sse4.2",
+    "ssse3",
+    "xsave",
+    "xsavec",
+    "xsaveopt",
+    "xsaves",
+];
+
+const AARCH64_FEATURES: [&str; 14] = [
+    "aes", "bf16", "crc", "dotprod", "fp16", "i8mm", "lse", "neon", "rand", "rcpc", "sha2", "sha3",
+    "sve", "sve2",
+];
@@ -19,6 +19,10 @@ pub struct CompletionConfig {
     pub snippet_cap: Option<SnippetCap>,
     pub insert_use: InsertUseConfig,
     pub snippets: Vec<Snippet>,
+    /// Unstable feature names known to the active toolchain, completed in
+    /// `#![feature(...)]` in addition to the ones we ship with. Filled from the
+    /// `completion.extraFeatures` setting.
+    pub toolchain_features: Vec<String>,
 }
 
 impl CompletionConfig {
@@ -325,6 +325,22 @@ impl<'a> CompletionContext<'a> {
         }
     }
 
+    /// Whether the current crate enables the given unstable feature in a `#![feature(...)]`.
+    pub(crate) fn is_feature_enabled(&self, feature: &str) -> bool {
+        let krate = match self.krate {
+            Some(it) => it,
+            None => return false,
+        };
+        let attrs = krate.root_module(self.db).attrs(self.db);
+        let enabled = attrs
+            .by_key("feature")
+            .attrs()
+            .filter_map(|attr| attr.parse_path_comma_token_tree())
+            .flatten()
+            .any(|path| path.as_ident().map_or(false, |name| name.to_smol_str() == feature));
+        enabled
+    }
+
     pub(crate) fn is_immediately_after_macro_bang(&self) -> bool {
         self.token.kind() == BANG && self.token.parent().map_or(false, |it| it.kind() == MACRO_CALL)
     }
@@ -853,6 +869,12 @@ impl<'a> CompletionContext<'a> {
                             && non_trivia_sibling(attr.syntax().clone().into(), syntax::Direction::Next).is_none();
                         let annotated_item_kind = if is_trailing_outer_attr {
                             None
+                        } else if attached.kind() == STMT_LIST {
+                            // inner attributes of a block annotate the block itself, or the
+                            // function whose body it is
+                            let block = attached.parent()?;
+                            let func = block.parent().filter(|it| it.kind() == FN);
+                            Some(func.unwrap_or(block).kind())
                         } else {
                             Some(attached.kind())
                         };
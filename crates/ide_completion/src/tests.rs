@@ -73,6 +73,7 @@ pub(crate) const TEST_CONFIG: CompletionConfig = CompletionConfig {
         skip_glob_imports: true,
     },
     snippets: Vec::new(),
+    toolchain_features: Vec::new(),
 };
 
 pub(crate) fn completion_list(ra_fixture: &str) -> String {
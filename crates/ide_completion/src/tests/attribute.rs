@@ -16,7 +16,7 @@ fn proc_macros() {
 #[$0]
 struct Foo;
 "#,
-        expect![[r#"
+        expect![[r##"
             md proc_macros
             kw self::
             kw super::
@@ -24,7 +24,10 @@ struct Foo;
             at allow(…)
             at cfg(…)
             at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
             at deny(…)
+            at expect(…)
             at forbid(…)
             at warn(…)
             at deprecated
@@ -36,7 +39,14 @@ struct Foo;
             at derive(…)
             at repr(…)
             at non_exhaustive
-        "#]],
+            at #![crate_name = ""]    crate attribute
+            at #![feature(…)]         crate attribute
+            at #![no_main]            crate attribute
+            at #![no_std]             crate attribute
+            at #![recursion_limit = "…"] crate attribute
+            at #![type_length_limit = "…"] crate attribute
+            at #![windows_subsystem = "…"] crate attribute
+        "##]],
     )
 }
 
@@ -67,9 +77,49 @@ struct Foo;
     )
 }
 
+#[test]
+fn proc_macros_qualified_only_attrs() {
+    check(
+        r#"
+//- proc_macros: identity, derive_identity, input_replace, mirror
+#[proc_macros::$0]
+struct Foo;
+"#,
+        expect![[r#"
+            at input_replace pub macro input_replace
+            at identity      pub macro identity
+        "#]],
+    );
+}
+
 #[test]
 fn inside_nested_attr() {
-    check(r#"#[cfg($0)]"#, expect![[]])
+    check(
+        r#"#[cfg($0)]"#,
+        expect![[r#"
+            ba debug_assertions
+            ba doc
+            ba doctest
+            ba feature
+            ba miri
+            ba overflow_checks
+            ba panic
+            ba proc_macro
+            ba target_abi
+            ba target_arch
+            ba target_endian
+            ba target_env
+            ba target_family
+            ba target_has_atomic
+            ba target_os
+            ba target_pointer_width
+            ba target_vendor
+            ba test
+            ba unix
+            ba windows
+            ba not(feature = "…")
+        "#]],
+    )
 }
 
 #[test]
@@ -83,7 +133,10 @@ fn with_existing_attr() {
             at allow(…)
             at cfg(…)
             at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
             at deny(…)
+            at expect(…)
             at forbid(…)
             at warn(…)
         "#]],
@@ -101,7 +154,48 @@ fn attr_on_source_file() {
             at allow(…)
             at cfg(…)
             at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
+            at deny(…)
+            at expect(…)
+            at forbid(…)
+            at warn(…)
+            at deprecated
+            at doc = "…"
+            at doc(hidden)
+            at doc(alias = "…")
+            at must_use
+            at no_mangle
+            at crate_name = ""
+            at feature(…)
+            at no_implicit_prelude
+            at no_main
+            at no_std
+            at recursion_limit = "…"
+            at type_length_limit = "…"
+            at windows_subsystem = "…"
+        "#]],
+    );
+}
+
+#[test]
+fn attr_on_source_file_with_feature() {
+    check(
+        r#"
+#![feature(no_core)]
+#![$0]
+"#,
+        expect![[r#"
+            kw self::
+            kw super::
+            kw crate::
+            at allow(…)
+            at cfg(…)
+            at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
             at deny(…)
+            at expect(…)
             at forbid(…)
             at warn(…)
             at deprecated
@@ -112,11 +206,51 @@ fn attr_on_source_file() {
             at no_mangle
             at crate_name = ""
             at feature(…)
+            at no_core
             at no_implicit_prelude
             at no_main
             at no_std
             at recursion_limit = "…"
-            at type_length_limit = …
+            at type_length_limit = "…"
+            at windows_subsystem = "…"
+        "#]],
+    );
+}
+
+#[test]
+fn attr_on_non_root_source_file() {
+    check(
+        r#"
+//- /main.rs
+mod foo;
+//- /foo.rs
+#![$0]
+"#,
+        expect![[r#"
+            kw self::
+            kw super::
+            kw crate::
+            at allow(…)
+            at cfg(…)
+            at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
+            at deny(…)
+            at expect(…)
+            at forbid(…)
+            at warn(…)
+            at deprecated
+            at doc = "…"
+            at doc(hidden)
+            at doc(alias = "…")
+            at must_use
+            at no_mangle
+            at crate_name = ""
+            at feature(…)
+            at no_implicit_prelude
+            at no_main
+            at recursion_limit = "…"
+            at type_length_limit = "…"
             at windows_subsystem = "…"
         "#]],
     );
@@ -126,14 +260,17 @@ fn attr_on_source_file() {
 fn attr_on_module() {
     check(
         r#"#[$0] mod foo;"#,
-        expect![[r#"
+        expect![[r##"
             kw self::
             kw super::
             kw crate::
             at allow(…)
             at cfg(…)
             at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
             at deny(…)
+            at expect(…)
             at forbid(…)
             at warn(…)
             at deprecated
@@ -143,8 +280,16 @@ fn attr_on_module() {
             at must_use
             at no_mangle
             at macro_use
+            at no_implicit_prelude
             at path = "…"
-        "#]],
+            at #![crate_name = ""]    crate attribute
+            at #![feature(…)]         crate attribute
+            at #![no_main]            crate attribute
+            at #![no_std]             crate attribute
+            at #![recursion_limit = "…"] crate attribute
+            at #![type_length_limit = "…"] crate attribute
+            at #![windows_subsystem = "…"] crate attribute
+        "##]],
     );
     check(
         r#"mod foo {#![$0]}"#,
@@ -155,7 +300,10 @@ fn attr_on_module() {
             at allow(…)
             at cfg(…)
             at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
             at deny(…)
+            at expect(…)
             at forbid(…)
             at warn(…)
             at deprecated
@@ -169,18 +317,36 @@ fn attr_on_module() {
     );
 }
 
+#[test]
+fn no_implicit_prelude_positions() {
+    check_edit("no_implicit_prelude", r#"#![$0]"#, r#"#![no_implicit_prelude]"#);
+    check_edit(
+        "no_implicit_prelude",
+        r#"#[$0] mod foo { }"#,
+        r#"#[no_implicit_prelude] mod foo { }"#,
+    );
+    check_edit(
+        "no_implicit_prelude",
+        r#"mod foo { #![$0] }"#,
+        r#"mod foo { #![no_implicit_prelude] }"#,
+    );
+}
+
 #[test]
 fn attr_on_macro_rules() {
     check(
         r#"#[$0] macro_rules! foo {}"#,
-        expect![[r#"
+        expect![[r##"
             kw self::
             kw super::
             kw crate::
             at allow(…)
             at cfg(…)
             at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
             at deny(…)
+            at expect(…)
             at forbid(…)
             at warn(…)
             at deprecated
@@ -191,7 +357,14 @@ fn attr_on_macro_rules() {
             at no_mangle
             at macro_export
             at macro_use
-        "#]],
+            at #![crate_name = ""]    crate attribute
+            at #![feature(…)]         crate attribute
+            at #![no_main]            crate attribute
+            at #![no_std]             crate attribute
+            at #![recursion_limit = "…"] crate attribute
+            at #![type_length_limit = "…"] crate attribute
+            at #![windows_subsystem = "…"] crate attribute
+        "##]],
     );
 }
 
@@ -199,14 +372,17 @@ fn attr_on_macro_rules() {
 fn attr_on_macro_def() {
     check(
         r#"#[$0] macro foo {}"#,
-        expect![[r#"
+        expect![[r##"
             kw self::
             kw super::
             kw crate::
             at allow(…)
             at cfg(…)
             at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
             at deny(…)
+            at expect(…)
             at forbid(…)
             at warn(…)
             at deprecated
@@ -215,7 +391,14 @@ fn attr_on_macro_def() {
             at doc(alias = "…")
             at must_use
             at no_mangle
-        "#]],
+            at #![crate_name = ""]    crate attribute
+            at #![feature(…)]         crate attribute
+            at #![no_main]            crate attribute
+            at #![no_std]             crate attribute
+            at #![recursion_limit = "…"] crate attribute
+            at #![type_length_limit = "…"] crate attribute
+            at #![windows_subsystem = "…"] crate attribute
+        "##]],
     );
 }
 
@@ -223,14 +406,17 @@ fn attr_on_macro_def() {
 fn attr_on_extern_crate() {
     check(
         r#"#[$0] extern crate foo;"#,
-        expect![[r#"
+        expect![[r##"
             kw self::
             kw super::
             kw crate::
             at allow(…)
             at cfg(…)
             at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
             at deny(…)
+            at expect(…)
             at forbid(…)
             at warn(…)
             at deprecated
@@ -240,7 +426,14 @@ fn attr_on_extern_crate() {
             at must_use
             at no_mangle
             at macro_use
-        "#]],
+            at #![crate_name = ""]    crate attribute
+            at #![feature(…)]         crate attribute
+            at #![no_main]            crate attribute
+            at #![no_std]             crate attribute
+            at #![recursion_limit = "…"] crate attribute
+            at #![type_length_limit = "…"] crate attribute
+            at #![windows_subsystem = "…"] crate attribute
+        "##]],
     );
 }
 
@@ -248,14 +441,17 @@ fn attr_on_extern_crate() {
 fn attr_on_use() {
     check(
         r#"#[$0] use foo;"#,
-        expect![[r#"
+        expect![[r##"
             kw self::
             kw super::
             kw crate::
             at allow(…)
             at cfg(…)
             at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
             at deny(…)
+            at expect(…)
             at forbid(…)
             at warn(…)
             at deprecated
@@ -264,7 +460,14 @@ fn attr_on_use() {
             at doc(alias = "…")
             at must_use
             at no_mangle
-        "#]],
+            at #![crate_name = ""]    crate attribute
+            at #![feature(…)]         crate attribute
+            at #![no_main]            crate attribute
+            at #![no_std]             crate attribute
+            at #![recursion_limit = "…"] crate attribute
+            at #![type_length_limit = "…"] crate attribute
+            at #![windows_subsystem = "…"] crate attribute
+        "##]],
     );
 }
 
@@ -272,14 +475,17 @@ fn attr_on_use() {
 fn attr_on_type_alias() {
     check(
         r#"#[$0] type foo = ();"#,
-        expect![[r#"
+        expect![[r##"
             kw self::
             kw super::
             kw crate::
             at allow(…)
             at cfg(…)
             at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
             at deny(…)
+            at expect(…)
             at forbid(…)
             at warn(…)
             at deprecated
@@ -288,7 +494,29 @@ fn attr_on_type_alias() {
             at doc(alias = "…")
             at must_use
             at no_mangle
-        "#]],
+            at #![crate_name = ""]    crate attribute
+            at #![feature(…)]         crate attribute
+            at #![no_main]            crate attribute
+            at #![no_std]             crate attribute
+            at #![recursion_limit = "…"] crate attribute
+            at #![type_length_limit = "…"] crate attribute
+            at #![windows_subsystem = "…"] crate attribute
+        "##]],
+    );
+}
+
+#[test]
+fn must_use_on_type_alias() {
+    check_edit(
+        "must_use",
+        r#"
+#[$0]
+type Fallible<T> = Result<T, ()>;
+"#,
+        r#"
+#[must_use]
+type Fallible<T> = Result<T, ()>;
+"#,
     );
 }
 
@@ -300,16 +528,19 @@ fn attr_on_struct() {
 #[$0]
 struct Foo;
 "#,
-        expect![[r#"
+        expect![[r##"
             md core
-            at derive           pub macro derive
+            at derive                 pub macro derive
             kw self::
             kw super::
             kw crate::
             at allow(…)
             at cfg(…)
             at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
             at deny(…)
+            at expect(…)
             at forbid(…)
             at warn(…)
             at deprecated
@@ -321,7 +552,14 @@ struct Foo;
             at derive(…)
             at repr(…)
             at non_exhaustive
-        "#]],
+            at #![crate_name = ""]    crate attribute
+            at #![feature(…)]         crate attribute
+            at #![no_main]            crate attribute
+            at #![no_std]             crate attribute
+            at #![recursion_limit = "…"] crate attribute
+            at #![type_length_limit = "…"] crate attribute
+            at #![windows_subsystem = "…"] crate attribute
+        "##]],
     );
 }
 
@@ -329,14 +567,17 @@ struct Foo;
 fn attr_on_enum() {
     check(
         r#"#[$0] enum Foo {}"#,
-        expect![[r#"
+        expect![[r##"
             kw self::
             kw super::
             kw crate::
             at allow(…)
             at cfg(…)
             at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
             at deny(…)
+            at expect(…)
             at forbid(…)
             at warn(…)
             at deprecated
@@ -348,7 +589,14 @@ fn attr_on_enum() {
             at derive(…)
             at repr(…)
             at non_exhaustive
-        "#]],
+            at #![crate_name = ""]    crate attribute
+            at #![feature(…)]         crate attribute
+            at #![no_main]            crate attribute
+            at #![no_std]             crate attribute
+            at #![recursion_limit = "…"] crate attribute
+            at #![type_length_limit = "…"] crate attribute
+            at #![windows_subsystem = "…"] crate attribute
+        "##]],
     );
 }
 
@@ -356,14 +604,17 @@ fn attr_on_enum() {
 fn attr_on_const() {
     check(
         r#"#[$0] const FOO: () = ();"#,
-        expect![[r#"
+        expect![[r##"
             kw self::
             kw super::
             kw crate::
             at allow(…)
             at cfg(…)
             at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
             at deny(…)
+            at expect(…)
             at forbid(…)
             at warn(…)
             at deprecated
@@ -372,7 +623,14 @@ fn attr_on_const() {
             at doc(alias = "…")
             at must_use
             at no_mangle
-        "#]],
+            at #![crate_name = ""]    crate attribute
+            at #![feature(…)]         crate attribute
+            at #![no_main]            crate attribute
+            at #![no_std]             crate attribute
+            at #![recursion_limit = "…"] crate attribute
+            at #![type_length_limit = "…"] crate attribute
+            at #![windows_subsystem = "…"] crate attribute
+        "##]],
     );
 }
 
@@ -380,14 +638,17 @@ fn attr_on_const() {
 fn attr_on_static() {
     check(
         r#"#[$0] static FOO: () = ()"#,
-        expect![[r#"
+        expect![[r##"
             kw self::
             kw super::
             kw crate::
             at allow(…)
             at cfg(…)
             at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
             at deny(…)
+            at expect(…)
             at forbid(…)
             at warn(…)
             at deprecated
@@ -401,7 +662,14 @@ fn attr_on_static() {
             at link_section = "…"
             at global_allocator
             at used
-        "#]],
+            at #![crate_name = ""]    crate attribute
+            at #![feature(…)]         crate attribute
+            at #![no_main]            crate attribute
+            at #![no_std]             crate attribute
+            at #![recursion_limit = "…"] crate attribute
+            at #![type_length_limit = "…"] crate attribute
+            at #![windows_subsystem = "…"] crate attribute
+        "##]],
     );
 }
 
@@ -409,14 +677,17 @@ fn attr_on_static() {
 fn attr_on_trait() {
     check(
         r#"#[$0] trait Foo {}"#,
-        expect![[r#"
+        expect![[r##"
             kw self::
             kw super::
             kw crate::
             at allow(…)
             at cfg(…)
             at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
             at deny(…)
+            at expect(…)
             at forbid(…)
             at warn(…)
             at deprecated
@@ -426,7 +697,14 @@ fn attr_on_trait() {
             at must_use
             at no_mangle
             at must_use
-        "#]],
+            at #![crate_name = ""]    crate attribute
+            at #![feature(…)]         crate attribute
+            at #![no_main]            crate attribute
+            at #![no_std]             crate attribute
+            at #![recursion_limit = "…"] crate attribute
+            at #![type_length_limit = "…"] crate attribute
+            at #![windows_subsystem = "…"] crate attribute
+        "##]],
     );
 }
 
@@ -434,14 +712,17 @@ fn attr_on_trait() {
 fn attr_on_impl() {
     check(
         r#"#[$0] impl () {}"#,
-        expect![[r#"
+        expect![[r##"
             kw self::
             kw super::
             kw crate::
             at allow(…)
             at cfg(…)
             at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
             at deny(…)
+            at expect(…)
             at forbid(…)
             at warn(…)
             at deprecated
@@ -451,7 +732,14 @@ fn attr_on_impl() {
             at must_use
             at no_mangle
             at automatically_derived
-        "#]],
+            at #![crate_name = ""]    crate attribute
+            at #![feature(…)]         crate attribute
+            at #![no_main]            crate attribute
+            at #![no_std]             crate attribute
+            at #![recursion_limit = "…"] crate attribute
+            at #![type_length_limit = "…"] crate attribute
+            at #![windows_subsystem = "…"] crate attribute
+        "##]],
     );
     check(
         r#"impl () {#![$0]}"#,
@@ -462,7 +750,10 @@ fn attr_on_impl() {
             at allow(…)
             at cfg(…)
             at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
             at deny(…)
+            at expect(…)
             at forbid(…)
             at warn(…)
             at deprecated
@@ -479,14 +770,17 @@ fn attr_on_impl() {
 fn attr_on_extern_block() {
     check(
         r#"#[$0] extern {}"#,
-        expect![[r#"
+        expect![[r##"
             kw self::
             kw super::
             kw crate::
             at allow(…)
             at cfg(…)
             at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
             at deny(…)
+            at expect(…)
             at forbid(…)
             at warn(…)
             at deprecated
@@ -496,7 +790,14 @@ fn attr_on_extern_block() {
             at must_use
             at no_mangle
             at link
-        "#]],
+            at #![crate_name = ""]    crate attribute
+            at #![feature(…)]         crate attribute
+            at #![no_main]            crate attribute
+            at #![no_std]             crate attribute
+            at #![recursion_limit = "…"] crate attribute
+            at #![type_length_limit = "…"] crate attribute
+            at #![windows_subsystem = "…"] crate attribute
+        "##]],
     );
     check(
         r#"extern {#![$0]}"#,
@@ -507,7 +808,10 @@ fn attr_on_extern_block() {
             at allow(…)
             at cfg(…)
             at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
             at deny(…)
+            at expect(…)
             at forbid(…)
             at warn(…)
             at deprecated
@@ -521,6 +825,64 @@ fn attr_on_extern_block() {
     );
 }
 
+#[test]
+fn link_name_prefills_item_name() {
+    check_edit(
+        "link_name",
+        r#"extern "C" { #[$0] fn foo(); }"#,
+        r#"extern "C" { #[link_name = "${0:foo}"] fn foo(); }"#,
+    );
+    check_edit(
+        "link_name",
+        r#"extern "C" { #[$0] static BAR: u8; }"#,
+        r#"extern "C" { #[link_name = "${0:BAR}"] static BAR: u8; }"#,
+    );
+}
+
+#[test]
+fn unsafe_attrs_by_edition() {
+    check_edit(
+        "no_mangle",
+        r#"
+//- /main.rs crate:main edition:2024
+#[$0] fn foo() {}
+"#,
+        r#"
+#[unsafe(no_mangle)] fn foo() {}
+"#,
+    );
+    check_edit(
+        "export_name",
+        r#"
+//- /main.rs crate:main edition:2024
+#[$0] fn foo() {}
+"#,
+        r#"
+#[unsafe(export_name = "${0:exported_symbol_name}")] fn foo() {}
+"#,
+    );
+    check_edit(
+        "no_mangle",
+        r#"
+//- /main.rs crate:main edition:2021
+#[$0] fn foo() {}
+"#,
+        r#"
+#[no_mangle] fn foo() {}
+"#,
+    );
+}
+
+#[test]
+fn cfg_attr_patterns() {
+    check_edit(
+        r#"cfg_attr(feature = "…", …)"#,
+        r#"#[$0] struct S;"#,
+        r#"#[cfg_attr(feature = "$1", $0)] struct S;"#,
+    );
+    check_edit("cfg_attr(test, …)", r#"#[$0] struct S;"#, r#"#[cfg_attr(test, $0)] struct S;"#);
+}
+
 #[test]
 fn attr_on_variant() {
     check(
@@ -532,7 +894,10 @@ fn attr_on_variant() {
             at allow(…)
             at cfg(…)
             at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
             at deny(…)
+            at expect(…)
             at forbid(…)
             at warn(…)
             at non_exhaustive
@@ -540,18 +905,101 @@ fn attr_on_variant() {
     );
 }
 
+#[test]
+fn attr_on_generic_params() {
+    check(
+        r#"fn f<#[$0] T, const N: usize>() {}"#,
+        expect![[r#"
+            kw self::
+            kw super::
+            kw crate::
+            at allow(…)
+            at cfg(…)
+            at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
+            at deny(…)
+            at expect(…)
+            at forbid(…)
+            at warn(…)
+        "#]],
+    );
+    check(
+        r#"fn f<T, #[$0] const N: usize>() {}"#,
+        expect![[r#"
+            kw self::
+            kw super::
+            kw crate::
+            at allow(…)
+            at cfg(…)
+            at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
+            at deny(…)
+            at expect(…)
+            at forbid(…)
+            at warn(…)
+        "#]],
+    );
+    check(
+        r#"fn f<#[$0] 'a>() {}"#,
+        expect![[r#"
+            kw self::
+            kw super::
+            kw crate::
+            at allow(…)
+            at cfg(…)
+            at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
+            at deny(…)
+            at expect(…)
+            at forbid(…)
+            at warn(…)
+        "#]],
+    );
+}
+
+#[test]
+fn attr_on_lifetime_param_with_feature() {
+    check(
+        r#"
+#![feature(dropck_eyepatch)]
+unsafe impl<#[$0] 'a> Drop for Foo<'a> {}
+"#,
+        expect![[r#"
+            kw self::
+            kw super::
+            kw crate::
+            at allow(…)
+            at cfg(…)
+            at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
+            at deny(…)
+            at expect(…)
+            at forbid(…)
+            at warn(…)
+            at may_dangle
+        "#]],
+    );
+}
+
 #[test]
 fn attr_on_fn() {
     check(
         r#"#[$0] fn main() {}"#,
-        expect![[r#"
+        expect![[r##"
             kw self::
             kw super::
             kw crate::
             at allow(…)
             at cfg(…)
             at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
             at deny(…)
+            at expect(…)
             at forbid(…)
             at warn(…)
             at deprecated
@@ -564,18 +1012,20 @@ fn attr_on_fn() {
             at link_name = "…"
             at link_section = "…"
             at cold
-            at ignore = "…"
             at inline
             at must_use
-            at panic_handler
-            at proc_macro
-            at proc_macro_derive(…)
-            at proc_macro_attribute
-            at should_panic
-            at target_feature = "…"
+            at panic_handler          fn(&PanicInfo) -> !
+            at target_feature(enable = "…")
             at test
             at track_caller
-        "#]],
+            at #![crate_name = ""]    crate attribute
+            at #![feature(…)]         crate attribute
+            at #![no_main]            crate attribute
+            at #![no_std]             crate attribute
+            at #![recursion_limit = "…"] crate attribute
+            at #![type_length_limit = "…"] crate attribute
+            at #![windows_subsystem = "…"] crate attribute
+        "##]],
     );
 }
 
@@ -591,7 +1041,10 @@ fn attr_on_expr() {
             at allow(…)
             at cfg(…)
             at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
             at deny(…)
+            at expect(…)
             at forbid(…)
             at warn(…)
         "#]],
@@ -599,64 +1052,1174 @@ fn attr_on_expr() {
 }
 
 #[test]
-fn attr_in_source_file_end() {
+fn coverage_with_feature() {
     check(
-        r#"#[$0]"#,
-        expect![[r#"
+        r#"
+#![feature(coverage_attribute)]
+#[$0]
+impl Foo {}
+"#,
+        expect![[r##"
             kw self::
             kw super::
             kw crate::
             at allow(…)
-            at automatically_derived
             at cfg(…)
             at cfg_attr(…)
-            at cold
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
             at deny(…)
+            at expect(…)
+            at forbid(…)
+            at warn(…)
             at deprecated
-            at derive(…)
             at doc = "…"
-            at doc(alias = "…")
             at doc(hidden)
-            at export_name = "…"
-            at forbid(…)
-            at global_allocator
-            at ignore = "…"
-            at inline
-            at link
-            at link_name = "…"
-            at link_section = "…"
-            at macro_export
-            at macro_use
+            at doc(alias = "…")
             at must_use
             at no_mangle
-            at non_exhaustive
-            at panic_handler
-            at path = "…"
-            at proc_macro
-            at proc_macro_attribute
-            at proc_macro_derive(…)
-            at repr(…)
-            at should_panic
-            at target_feature = "…"
-            at test
-            at track_caller
-            at used
-            at warn(…)
+            at automatically_derived
+            at coverage(…)
+            at #![crate_name = ""]    crate attribute
+            at #![feature(…)]         crate attribute
+            at #![no_main]            crate attribute
+            at #![no_std]             crate attribute
+            at #![recursion_limit = "…"] crate attribute
+            at #![type_length_limit = "…"] crate attribute
+            at #![windows_subsystem = "…"] crate attribute
+        "##]],
+    );
+}
+
+#[test]
+fn coverage_input() {
+    check(
+        r#"#[coverage($0)] fn f() {}"#,
+        expect![[r#"
+            ba on
+            ba off
         "#]],
     );
 }
 
-mod cfg {
-    use super::*;
+#[test]
+fn instruction_set_input() {
+    check(
+        r#"
+//- /main.rs crate:main cfg:target_arch=arm
+#[instruction_set($0)] fn f() {}
+"#,
+        expect![[r#"
+            ba arm::a32
+            ba arm::t32
+        "#]],
+    );
+    check(
+        r#"
+//- /main.rs crate:main cfg:target_arch=arm
+#[instruction_set(arm::$0)] fn f() {}
+"#,
+        expect![[r#"
+            ba a32
+            ba t32
+        "#]],
+    );
+    check_edit(
+        "arm::t32",
+        r#"
+//- /main.rs crate:main cfg:target_arch=arm
+#[instruction_set($0)] fn f() {}
+"#,
+        r#"
+#[instruction_set(arm::t32)] fn f() {}
+"#,
+    );
+    // only 32-bit ARM targets and functions have instruction sets
+    check(
+        r#"
+//- /main.rs crate:main cfg:target_arch=aarch64
+#[instruction_set($0)] fn f() {}
+"#,
+        expect![[r#""#]],
+    );
+    check(
+        r#"
+//- /main.rs crate:main cfg:target_arch=arm
+#[instruction_set($0)] struct S;
+"#,
+        expect![[r#""#]],
+    );
+}
+
+#[test]
+fn attr_on_fn_in_proc_macro_crate() {
+    check(
+        r#"
+//- /lib.rs crate:pm crate-type:proc-macro
+#[$0]
+pub fn mac(input: TokenStream) -> TokenStream { input }
+"#,
+        expect![[r##"
+            kw self::
+            kw super::
+            kw crate::
+            at allow(…)
+            at cfg(…)
+            at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
+            at deny(…)
+            at expect(…)
+            at forbid(…)
+            at warn(…)
+            at deprecated
+            at doc = "…"
+            at doc(hidden)
+            at doc(alias = "…")
+            at must_use
+            at no_mangle
+            at export_name = "…"
+            at link_name = "…"
+            at link_section = "…"
+            at cold
+            at inline
+            at must_use
+            at panic_handler          fn(&PanicInfo) -> !
+            at proc_macro
+            at proc_macro_derive(…)
+            at proc_macro_attribute
+            at target_feature(enable = "…")
+            at test
+            at track_caller
+            at #![crate_name = ""]    crate attribute
+            at #![feature(…)]         crate attribute
+            at #![no_main]            crate attribute
+            at #![no_std]             crate attribute
+            at #![recursion_limit = "…"] crate attribute
+            at #![type_length_limit = "…"] crate attribute
+            at #![windows_subsystem = "…"] crate attribute
+        "##]],
+    );
+}
+
+#[test]
+fn attr_on_trait_with_features() {
+    check(
+        r#"
+#![feature(const_trait_impl, marker_trait_attr)]
+#[$0]
+trait Foo {}
+"#,
+        expect![[r##"
+            kw self::
+            kw super::
+            kw crate::
+            at allow(…)
+            at cfg(…)
+            at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
+            at deny(…)
+            at expect(…)
+            at forbid(…)
+            at warn(…)
+            at deprecated
+            at doc = "…"
+            at doc(hidden)
+            at doc(alias = "…")
+            at must_use
+            at no_mangle
+            at const_trait
+            at marker
+            at must_use
+            at #![crate_name = ""]    crate attribute
+            at #![feature(…)]         crate attribute
+            at #![no_main]            crate attribute
+            at #![no_std]             crate attribute
+            at #![recursion_limit = "…"] crate attribute
+            at #![type_length_limit = "…"] crate attribute
+            at #![windows_subsystem = "…"] crate attribute
+        "##]],
+    );
+}
+
+#[test]
+fn attr_on_impl_with_features() {
+    check(
+        r#"
+#![feature(do_not_recommend)]
+#[$0]
+impl Foo for () {}
+"#,
+        expect![[r##"
+            kw self::
+            kw super::
+            kw crate::
+            at allow(…)
+            at cfg(…)
+            at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
+            at deny(…)
+            at expect(…)
+            at forbid(…)
+            at warn(…)
+            at deprecated
+            at doc = "…"
+            at doc(hidden)
+            at doc(alias = "…")
+            at must_use
+            at no_mangle
+            at automatically_derived
+            at do_not_recommend
+            at #![crate_name = ""]    crate attribute
+            at #![feature(…)]         crate attribute
+            at #![no_main]            crate attribute
+            at #![no_std]             crate attribute
+            at #![recursion_limit = "…"] crate attribute
+            at #![type_length_limit = "…"] crate attribute
+            at #![windows_subsystem = "…"] crate attribute
+        "##]],
+    );
+}
+
+#[test]
+fn panic_handler_only_once() {
+    check(
+        r#"
+#[panic_handler]
+fn panic(_: &PanicInfo) -> ! { loop {} }
+#[$0]
+fn other() {}
+"#,
+        expect![[r#"
+            kw self::
+            kw super::
+            kw crate::
+            at allow(…)
+            at cfg(…)
+            at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
+            at deny(…)
+            at expect(…)
+            at forbid(…)
+            at warn(…)
+            at deprecated
+            at doc = "…"
+            at doc(hidden)
+            at doc(alias = "…")
+            at must_use
+            at no_mangle
+            at export_name = "…"
+            at link_name = "…"
+            at link_section = "…"
+            at cold
+            at inline
+            at must_use
+            at target_feature(enable = "…")
+            at test
+            at track_caller
+        "#]],
+    );
+}
+
+#[test]
+fn attr_on_test_fn() {
+    check(
+        r#"#[test] #[$0] fn test_foo() {}"#,
+        expect![[r#"
+            kw self::
+            kw super::
+            kw crate::
+            at allow(…)
+            at cfg(…)
+            at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
+            at deny(…)
+            at expect(…)
+            at forbid(…)
+            at warn(…)
+            at deprecated
+            at doc = "…"
+            at doc(hidden)
+            at doc(alias = "…")
+            at must_use
+            at no_mangle
+            at export_name = "…"
+            at link_name = "…"
+            at link_section = "…"
+            at cold
+            at ignore = "…"
+            at inline
+            at must_use
+            at panic_handler          fn(&PanicInfo) -> !
+            at should_panic
+            at target_feature(enable = "…")
+            at test
+            at track_caller
+        "#]],
+    );
+    check(
+        r#"#[$0] #[tokio::test] async fn test_foo() {}"#,
+        expect![[r##"
+            kw self::
+            kw super::
+            kw crate::
+            at allow(…)
+            at cfg(…)
+            at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
+            at deny(…)
+            at expect(…)
+            at forbid(…)
+            at warn(…)
+            at deprecated
+            at doc = "…"
+            at doc(hidden)
+            at doc(alias = "…")
+            at must_use
+            at no_mangle
+            at export_name = "…"
+            at link_name = "…"
+            at link_section = "…"
+            at cold
+            at ignore = "…"
+            at inline
+            at must_use
+            at panic_handler          fn(&PanicInfo) -> !
+            at should_panic
+            at target_feature(enable = "…")
+            at test
+            at track_caller
+            at #![crate_name = ""]    crate attribute
+            at #![feature(…)]         crate attribute
+            at #![no_main]            crate attribute
+            at #![no_std]             crate attribute
+            at #![recursion_limit = "…"] crate attribute
+            at #![type_length_limit = "…"] crate attribute
+            at #![windows_subsystem = "…"] crate attribute
+        "##]],
+    );
+}
+
+#[test]
+fn clippy_config_attr_on_fn() {
+    check(
+        r#"#[clippy::$0] fn f() {}"#,
+        expect![[r#"
+            at cognitive_complexity = "…"
+        "#]],
+    );
+    check(r#"#[clippy::$0] struct S;"#, expect![[]]);
+}
+
+#[test]
+fn clippy_config_attr_inserts_value() {
+    check_edit(
+        "cognitive_complexity",
+        r#"#[clippy::$0] fn f() {}"#,
+        r#"#[clippy::cognitive_complexity = "${0:25}"] fn f() {}"#,
+    );
+}
+
+#[test]
+fn attr_on_closure() {
+    check(
+        r#"fn main() { let f = #[$0] || (); }"#,
+        expect![[r#"
+            kw self::
+            kw super::
+            kw crate::
+            at allow(…)
+            at cfg(…)
+            at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
+            at deny(…)
+            at expect(…)
+            at forbid(…)
+            at warn(…)
+        "#]],
+    );
+}
+
+#[test]
+fn attr_on_block_expr() {
+    check(
+        r#"fn main() { let x = #[$0] { 92 }; }"#,
+        expect![[r#"
+            kw self::
+            kw super::
+            kw crate::
+            at allow(…)
+            at cfg(…)
+            at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
+            at deny(…)
+            at expect(…)
+            at forbid(…)
+            at warn(…)
+        "#]],
+    );
+}
+
+#[test]
+fn attr_on_block_expr_stmt() {
+    check(
+        r#"fn main() { #[$0] { foo(); } }"#,
+        expect![[r#"
+            kw self::
+            kw super::
+            kw crate::
+            at allow(…)
+            at cfg(…)
+            at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
+            at deny(…)
+            at expect(…)
+            at forbid(…)
+            at warn(…)
+        "#]],
+    );
+}
+
+#[test]
+fn inner_attr_in_block_expr() {
+    check(
+        r#"fn main() { let x = { #![$0] 92 }; }"#,
+        expect![[r#"
+            kw self::
+            kw super::
+            kw crate::
+            at allow(…)
+            at cfg(…)
+            at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
+            at deny(…)
+            at expect(…)
+            at forbid(…)
+            at warn(…)
+        "#]],
+    );
+}
+
+#[test]
+fn inner_attr_in_closure_body() {
+    check(
+        r#"fn main() { let f = || { #![$0] }; }"#,
+        expect![[r#"
+            kw self::
+            kw super::
+            kw crate::
+            at allow(…)
+            at cfg(…)
+            at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
+            at deny(…)
+            at expect(…)
+            at forbid(…)
+            at warn(…)
+        "#]],
+    );
+}
+
+#[test]
+fn attr_in_source_file_end() {
+    check(
+        r#"#[$0]"#,
+        expect![[r#"
+            kw self::
+            kw super::
+            kw crate::
+            at allow(…)
+            at cfg(…)
+            at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
+            at cold
+            at deny(…)
+            at deprecated
+            at derive(…)
+            at doc = "…"
+            at doc(alias = "…")
+            at doc(hidden)
+            at expect(…)
+            at export_name = "…"
+            at forbid(…)
+            at global_allocator
+            at inline
+            at link
+            at link_name = "…"
+            at link_section = "…"
+            at macro_export
+            at macro_use
+            at must_use
+            at no_implicit_prelude
+            at no_mangle
+            at non_exhaustive
+            at panic_handler          fn(&PanicInfo) -> !
+            at path = "…"
+            at repr(…)
+            at target_feature(enable = "…")
+            at test
+            at track_caller
+            at used
+            at warn(…)
+        "#]],
+    );
+}
+
+#[test]
+fn trait_and_impl_attrs_stay_on_their_item() {
+    let cases: [(&str, &[&str]); 3] = [
+        ("#[$0]", &["automatically_derived", "const_trait", "do_not_recommend", "marker"]),
+        ("#[$0] impl Foo for () {}", &["const_trait", "marker"]),
+        ("#[$0] trait Foo {}", &["automatically_derived", "do_not_recommend"]),
+    ];
+    for (item, unexpected) in cases {
+        let completions = completion_list(&format!(
+            "#![feature(const_trait_impl, do_not_recommend, marker_trait_attr)]\n{}",
+            item
+        ));
+        for attr in unexpected {
+            let label = format!("at {}", attr);
+            assert!(
+                !completions.lines().any(|it| it.trim() == label),
+                "{} offered for `{}`",
+                attr,
+                item
+            );
+        }
+    }
+}
+
+#[test]
+fn attr_macros_under_path_roots() {
+    let fixture = |attr| {
+        format!(
+            r#"
+//- proc_macros: identity
+pub use proc_macros::identity;
+mod inner {{
+    pub use proc_macros::identity as inner_identity;
+    {}
+    fn f() {{}}
+}}
+"#,
+            attr
+        )
+    };
+    check(
+        &fixture("#[crate::$0]"),
+        expect![[r#"
+            md inner
+            at identity pub macro identity
+        "#]],
+    );
+    check(
+        &fixture("#[self::$0]"),
+        expect![[r#"
+            at inner_identity pub macro identity
+        "#]],
+    );
+    check(
+        &fixture("#[super::$0]"),
+        expect![[r#"
+            kw super::
+            md inner
+            at identity pub macro identity
+        "#]],
+    );
+}
+
+#[test]
+fn attrs_in_cfg_attr_slots() {
+    check(
+        r#"#[cfg_attr(unix, derive(Debug), $0)] struct Foo;"#,
+        expect![[r#"
+            at allow(…)
+            at cfg(…)
+            at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
+            at deny(…)
+            at expect(…)
+            at forbid(…)
+            at warn(…)
+            at deprecated
+            at doc = "…"
+            at doc(hidden)
+            at doc(alias = "…")
+            at must_use
+            at no_mangle
+            at repr(…)
+            at non_exhaustive
+        "#]],
+    );
+    check(
+        r#"#[cfg_attr(unix, repr(C), must_use, no_$0)] struct Foo;"#,
+        expect![[r#"
+        at allow(…)
+        at cfg(…)
+        at cfg_attr(…)
+        at cfg_attr(feature = "…", …)
+        at cfg_attr(test, …)
+        at deny(…)
+        at expect(…)
+        at forbid(…)
+        at warn(…)
+        at deprecated
+        at doc = "…"
+        at doc(hidden)
+        at doc(alias = "…")
+        at no_mangle
+        at derive(…)
+        at non_exhaustive
+    "#]],
+    );
+    check(r#"#[cfg_attr(unix, doc = $0)] struct Foo;"#, expect![[r#""#]]);
+    check_edit(
+        "must_use",
+        r#"#[cfg_attr(unix, derive(Debug), $0)] struct Foo;"#,
+        r#"#[cfg_attr(unix, derive(Debug), must_use)] struct Foo;"#,
+    );
+}
+
+#[test]
+fn attr_macros_from_prelude() {
+    check(
+        r#"
+//- proc_macros: identity
+//- /main.rs crate:main deps:std
+#[$0]
+fn f() {}
+//- /std.rs crate:std
+pub mod prelude {
+    pub mod rust_2018 {
+        pub use proc_macros::identity as test;
+        pub use proc_macros::identity as bench_it;
+    }
+}
+"#,
+        expect![[r##"
+            md std
+            md proc_macros
+            at bench_it               pub macro identity
+            at test                   pub macro identity
+            kw self::
+            kw super::
+            kw crate::
+            at allow(…)
+            at cfg(…)
+            at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
+            at deny(…)
+            at expect(…)
+            at forbid(…)
+            at warn(…)
+            at deprecated
+            at doc = "…"
+            at doc(hidden)
+            at doc(alias = "…")
+            at must_use
+            at no_mangle
+            at export_name = "…"
+            at link_name = "…"
+            at link_section = "…"
+            at cold
+            at inline
+            at must_use
+            at panic_handler          fn(&PanicInfo) -> !
+            at target_feature(enable = "…")
+            at track_caller
+            at #![crate_name = ""]    crate attribute
+            at #![feature(…)]         crate attribute
+            at #![no_main]            crate attribute
+            at #![no_std]             crate attribute
+            at #![recursion_limit = "…"] crate attribute
+            at #![type_length_limit = "…"] crate attribute
+            at #![windows_subsystem = "…"] crate attribute
+        "##]],
+    );
+}
+
+#[test]
+fn crate_attrs_at_top_of_crate_root() {
+    check(
+        r#"
+//- /main.rs crate:main
+#[$0]
+struct S;
+"#,
+        expect![[r##"
+            kw self::
+            kw super::
+            kw crate::
+            at allow(…)
+            at cfg(…)
+            at cfg_attr(…)
+            at cfg_attr(feature = "…", …)
+            at cfg_attr(test, …)
+            at deny(…)
+            at expect(…)
+            at forbid(…)
+            at warn(…)
+            at deprecated
+            at doc = "…"
+            at doc(hidden)
+            at doc(alias = "…")
+            at must_use
+            at no_mangle
+            at derive(…)
+            at repr(…)
+            at non_exhaustive
+            at #![crate_name = ""]    crate attribute
+            at #![feature(…)]         crate attribute
+            at #![no_main]            crate attribute
+            at #![no_std]             crate attribute
+            at #![recursion_limit = "…"] crate attribute
+            at #![type_length_limit = "…"] crate attribute
+            at #![windows_subsystem = "…"] crate attribute
+        "##]],
+    );
+}
+
+#[test]
+fn crate_attrs_after_inner_attrs() {
+    check_edit(
+        "no_std",
+        r#"
+#![allow(unused)]
+#[$0]
+fn f() {}
+"#,
+        r#"
+#![allow(unused)]
+#![no_std]
+fn f() {}
+"#,
+    );
+    check_edit(
+        "recursion_limit",
+        r#"
+#[$0]
+fn f() {}
+"#,
+        r#"
+#![recursion_limit = "${0:128}"]
+fn f() {}
+"#,
+    );
+}
+
+#[test]
+fn no_crate_attrs_when_not_ambiguous() {
+    let has_crate_attrs = |ra_fixture: &str| completion_list(ra_fixture).contains("#![");
+    // after another item
+    assert!(!has_crate_attrs("struct S;\n#[$0]\nfn f() {}"));
+    // after another outer attribute of the item
+    assert!(!has_crate_attrs("#[inline]\n#[$0]\nfn f() {}"));
+    // outside of the crate root
+    assert!(!has_crate_attrs("//- /main.rs crate:main\nmod m;\n//- /m.rs\n#[$0]\nfn f() {}"));
+}
+
+mod cfg {
+    use super::*;
+
+    #[test]
+    fn cfg_target_endian() {
+        check(
+            r#"#[cfg(target_endian = $0"#,
+            expect![[r#"
+                ba little
+                ba big
+            "#]],
+        );
+    }
+
+    #[test]
+    fn cfg_panic_strategy() {
+        check(
+            r#"#[cfg(panic = $0"#,
+            expect![[r#"
+                ba abort
+                ba unwind
+            "#]],
+        );
+    }
+
+    #[test]
+    fn cfg_target_pointer_width() {
+        check(
+            r#"#[cfg(target_pointer_width = $0"#,
+            expect![[r#"
+                ba 16
+                ba 32
+                ba 64
+            "#]],
+        );
+        check_edit(
+            "64",
+            r#"#[cfg(target_pointer_width = $0)] fn f() {}"#,
+            r#"#[cfg(target_pointer_width = "64")] fn f() {}"#,
+        );
+    }
+
+    #[test]
+    fn cfg_target_abi() {
+        check(
+            r#"#[cfg(target_abi = $0"#,
+            expect![[r#"
+                ba abi64
+                ba eabi
+                ba eabihf
+                ba elfv1
+                ba elfv2
+                ba fortanix
+                ba ilp32
+                ba llvm
+                ba macabi
+                ba sim
+                ba spe
+                ba uwp
+                ba x32
+            "#]],
+        );
+        check_edit(
+            "eabihf",
+            r#"#[cfg(target_abi = $0)] fn f() {}"#,
+            r#"#[cfg(target_abi = "eabihf")] fn f() {}"#,
+        );
+    }
+
+    #[test]
+    fn cfg_sanitize() {
+        check(
+            r#"
+#![feature(cfg_sanitize)]
+#[cfg(sanitize = $0)]
+fn f() {}
+"#,
+            expect![[r#"
+                ba address
+                ba cfi
+                ba hwaddress
+                ba kcfi
+                ba leak
+                ba memory
+                ba memtag
+                ba safestack
+                ba shadow-call-stack
+                ba thread
+            "#]],
+        );
+        check_edit(
+            "sanitize",
+            r#"
+#![feature(cfg_sanitize)]
+#[cfg($0)]
+fn f() {}
+"#,
+            r#"
+#![feature(cfg_sanitize)]
+#[cfg(sanitize = "$0")]
+fn f() {}
+"#,
+        );
+        // the key is unstable
+        check(r#"#[cfg(sanitize = $0)] fn f() {}"#, expect![[r#""#]]);
+        let list = completion_list(r#"#[cfg($0)] fn f() {}"#);
+        assert!(!list.contains("sanitize"));
+    }
+
+    #[test]
+    fn cfg_target_has_atomic() {
+        check(
+            r#"#[cfg(target_has_atomic = $0"#,
+            expect![[r#"
+                ba 8
+                ba 16
+                ba 32
+                ba 64
+                ba 128
+                ba ptr
+            "#]],
+        );
+        check_edit(
+            "target_has_atomic",
+            r#"#[cfg($0)] fn f() {}"#,
+            r#"#[cfg(target_has_atomic = "$0")] fn f() {}"#,
+        );
+    }
+
+    #[test]
+    fn cfg_not_feature() {
+        check_edit("not", r#"#[cfg($0)] fn f() {}"#, r#"#[cfg(not(feature = "$0"))] fn f() {}"#);
+        check_edit(
+            "not",
+            r#"#[cfg(all(unix, $0))] fn f() {}"#,
+            r#"#[cfg(all(unix, not(feature = "$0")))] fn f() {}"#,
+        );
+        // only offered where a predicate goes
+        let completions = completion_list(r#"#[cfg(target_os = $0)] fn f() {}"#);
+        assert!(!completions.contains("not("), "{}", completions);
+        let completions = completion_list(r#"#[cfg_attr(unix, $0)] fn f() {}"#);
+        assert!(!completions.contains("not("), "{}", completions);
+        // nor as the operand of `not` itself
+        let completions = completion_list(r#"#[cfg(not($0))] fn f() {}"#);
+        assert!(!completions.contains("not("), "{}", completions);
+        assert!(completions.contains("ba unix"), "{}", completions);
+        let completions = completion_list(r#"#[cfg(not( un$0))] fn f() {}"#);
+        assert!(!completions.contains("not("), "{}", completions);
+    }
+
+    #[test]
+    fn cfg_custom_keys() {
+        check(
+            r#"
+//- /main.rs crate:main cfg:my_custom,my_key=some_value
+#[cfg($0)]
+fn f() {}
+"#,
+            expect![[r#"
+                ba debug_assertions
+                ba doc
+                ba doctest
+                ba feature
+                ba miri
+                ba my_custom
+                ba my_key
+                ba overflow_checks
+                ba panic
+                ba proc_macro
+                ba target_abi
+                ba target_arch
+                ba target_endian
+                ba target_env
+                ba target_family
+                ba target_has_atomic
+                ba target_os
+                ba target_pointer_width
+                ba target_vendor
+                ba test
+                ba unix
+                ba windows
+                ba not(feature = "…")
+            "#]],
+        );
+    }
+
+    #[test]
+    fn cfg_key_value_inserts_eq() {
+        check_edit("target_os", r#"#[cfg($0)] fn f() {}"#, r#"#[cfg(target_os = "$0")] fn f() {}"#);
+        check_edit("unix", r#"#[cfg($0)] fn f() {}"#, r#"#[cfg(unix)] fn f() {}"#);
+    }
+
+    #[test]
+    fn cfg_rustdoc_flags() {
+        check_edit("doc", r#"#[cfg($0)] fn f() {}"#, r#"#[cfg(doc)] fn f() {}"#);
+        check_edit("doctest", r#"#[cfg($0)] fn f() {}"#, r#"#[cfg(doctest)] fn f() {}"#);
+        check_edit("doc", r#"#[cfg(not($0))] fn f() {}"#, r#"#[cfg(not(doc))] fn f() {}"#);
+    }
+
+    #[test]
+    fn cfg_boolean_flags() {
+        check_edit(
+            "debug_assertions",
+            r#"#[cfg($0)] fn f() {}"#,
+            r#"#[cfg(debug_assertions)] fn f() {}"#,
+        );
+        check_edit(
+            "overflow_checks",
+            r#"#[cfg(not($0))] fn f() {}"#,
+            r#"#[cfg(not(overflow_checks))] fn f() {}"#,
+        );
+    }
+
+    #[test]
+    fn cfg_custom_key_value() {
+        check(
+            r#"
+//- /main.rs crate:main cfg:my_custom,my_key=some_value
+#[cfg(my_key = $0)]
+fn f() {}
+"#,
+            expect![[r#"
+                ba some_value
+            "#]],
+        );
+    }
+
+    #[test]
+    fn cfg_attr_predicate() {
+        check(
+            r#"#[cfg_attr(target_endian = $0, allow(unused))] struct Foo;"#,
+            expect![[r#"
+                ba little
+                ba big
+            "#]],
+        );
+    }
+
+    #[test]
+    fn nested_cfg_attr_predicate() {
+        check(
+            r#"#[cfg_attr(unix, cfg_attr(all(target_endian = $0), allow(unused)))] struct Foo;"#,
+            expect![[r#"
+                ba little
+                ba big
+            "#]],
+        );
+    }
+
+    #[test]
+    fn nested_cfg_attr_attribute_input() {
+        check(
+            r#"#[cfg_attr(unix, cfg_attr(windows, repr($0)))] struct Foo;"#,
+            expect![[r#"
+                ba align(…)    alignment must be a power of two
+                ba packed      no padding, fields may be unaligned
+                ba transparent same layout as its single non-zero-sized field
+                ba C           C-compatible layout, fields keep their declaration order
+                ba u8          enum discriminant is a `u8`
+                ba u16         enum discriminant is a `u16`
+                ba u32         enum discriminant is a `u32`
+                ba u64         enum discriminant is a `u64`
+                ba u128        enum discriminant is a `u128`
+                ba usize       enum discriminant is a `usize`
+                ba i8          enum discriminant is an `i8`
+                ba i16         enum discriminant is an `i16`
+                ba i32         enum discriminant is an `i32`
+                ba i64         enum discriminant is an `i64`
+                ba i128        enum discriminant is an `i128`
+                ba isize       enum discriminant is an `isize`
+            "#]],
+        );
+    }
+}
+
+mod doc {
+    use super::*;
+
+    #[test]
+    fn doc_keys() {
+        check(
+            r#"#[doc($0)] fn f() {}"#,
+            expect![[r#"
+            ba alias = "…"
+            ba hidden
+            ba inline
+            ba no_inline
+        "#]],
+        );
+        check(
+            r#"
+#![feature(doc_cfg)]
+#[doc($0)] fn f() {}
+"#,
+            expect![[r#"
+                ba alias = "…"
+                ba cfg(…)
+                ba hidden
+                ba inline
+                ba no_inline
+            "#]],
+        );
+    }
+
+    #[test]
+    fn doc_cfg_predicate() {
+        check(
+            r#"
+#![feature(doc_cfg)]
+#[doc(cfg(target_endian = $0))] fn f() {}
+"#,
+            expect![[r#"
+                ba little
+                ba big
+            "#]],
+        );
+        check(r#"#[doc(cfg(target_endian = $0))] fn f() {}"#, expect![[]]);
+    }
+}
+
+mod limit {
+    use super::*;
+
+    #[test]
+    fn recursion_limit_value() {
+        check(
+            r#"#![recursion_limit = $0]"#,
+            expect![[r#"
+            ba "128" the default limit
+            kw self
+            kw super
+            kw crate
+            bt u32
+        "#]],
+        );
+        check_edit("\"128\"", r#"#![recursion_limit = $0]"#, r#"#![recursion_limit = "${0:128}"]"#);
+    }
+
+    #[test]
+    fn type_length_limit_value() {
+        check(
+            r#"#![type_length_limit = $0]"#,
+            expect![[r#"
+            ba "1048576" the default limit
+            kw self
+            kw super
+            kw crate
+            bt u32
+        "#]],
+        );
+    }
 
     #[test]
-    fn cfg_target_endian() {
+    fn bare_limit_gets_quoted() {
         check(
-            r#"#[cfg(target_endian = $0"#,
+            r#"#![recursion_limit = 256$0]"#,
             expect![[r#"
-                ba little
-                ba big
-            "#]],
+            ba "256" the limit has to be a quoted integer
+        "#]],
+        );
+        check_edit(
+            "\"256\"",
+            r#"#![recursion_limit = 256$0]"#,
+            r#"#![recursion_limit = "${0:256}"]"#,
+        );
+    }
+
+    #[test]
+    fn quoted_limit() {
+        check(r#"#![type_length_limit = "256$0"]"#, expect![[]]);
+        check(
+            r#"#![recursion_limit = "abc$0"]"#,
+            expect![[r#"
+            ba "128" the limit has to be an integer
+        "#]],
+        );
+    }
+
+    #[test]
+    fn other_name_value_attr() {
+        check(
+            r#"#[doc = $0] fn f() {}"#,
+            expect![[r#"
+            kw self
+            kw super
+            kw crate
+            fn f()   fn()
+            bt u32
+        "#]],
         );
     }
 }
@@ -688,13 +2251,109 @@ mod derive {
 #[derive($0)] struct Test;
 "#,
             expect![[r#"
-                de Default
-                de Clone, Copy
-                de PartialEq
-                de PartialEq, Eq
-                de PartialEq, Eq, PartialOrd, Ord
-                de Clone
-                de PartialEq, PartialOrd
+                de Default                builtin derive, defaults each field
+                de Clone, Copy            builtin derive, copies the value bitwise
+                de PartialEq              builtin derive, compares each field
+                de PartialEq, Eq          builtin derive, marks `==` as an equivalence
+                de PartialEq, Eq, PartialOrd, Ord builtin derive, orders fields lexicographically
+                de Clone                  builtin derive, clones each field
+                de PartialEq, PartialOrd  builtin derive, orders fields lexicographically
+            "#]],
+        );
+    }
+
+    #[test]
+    fn derive_on_union() {
+        check_derive(
+            r#"
+//- minicore: derive, copy, clone, ord, eq, default, fmt
+#[derive($0)] union Test { a: u8, b: u16 }
+"#,
+            expect![[r#"
+                de Clone, Copy builtin derive, copies the value bitwise
+                de Clone       builtin derive, clones each field
+            "#]],
+        );
+    }
+
+    #[test]
+    fn derive_args_offer_helpers() {
+        check_derive(
+            r#"
+//- /mac.rs crate:mac crate-type:proc-macro
+#[proc_macro_derive(MyTrait, attributes(my_helper, other))]
+pub fn derive(input: TokenStream) -> TokenStream { input }
+//- /main.rs crate:main deps:mac
+use mac::MyTrait;
+#[derive(MyTrait($0))]
+struct S;
+"#,
+            expect![[r#"
+                at my_helper
+                at other
+            "#]],
+        );
+        // builtin derives have no helpers
+        check_derive(
+            r#"
+//- minicore: derive, copy, clone, ord, eq, default, fmt
+#[derive(Clone($0))]
+struct S;
+"#,
+            expect![[r#""#]],
+        );
+    }
+
+    #[test]
+    fn unclosed_derive() {
+        check_derive(
+            r#"
+//- minicore: derive, copy, clone, ord, eq, default, fmt
+#[derive(PartialEq, Deb$0
+struct Test;
+"#,
+            expect![[r#"
+                de Default             builtin derive, defaults each field
+                de Clone, Copy         builtin derive, copies the value bitwise
+                de Eq                  builtin derive, marks `==` as an equivalence
+                de Eq, PartialOrd, Ord builtin derive, orders fields lexicographically
+                de Clone               builtin derive, clones each field
+                de PartialOrd          builtin derive, orders fields lexicographically
+            "#]],
+        );
+    }
+
+    #[test]
+    fn builtin_derive_docs_name_the_trait() {
+        let items = crate::tests::do_completion(
+            r#"
+//- minicore: derive, copy, clone
+#[derive($0)] struct Test;
+"#,
+            ide_db::SymbolKind::Derive.into(),
+        );
+        let docs = |label: &str| {
+            let item = items.iter().find(|it| it.label() == label).unwrap();
+            item.documentation().unwrap().as_str().to_owned()
+        };
+        assert_eq!(docs("Clone"), "implements `core::clone::Clone`");
+        assert!(docs("Clone, Copy").starts_with("implements `core::marker::Copy`"));
+    }
+
+    #[test]
+    fn derive_in_cfg_attr() {
+        check_derive(
+            r#"
+//- minicore: derive, copy, clone, ord, eq, default, fmt
+#[cfg_attr(test, derive(PartialEq, $0))] struct Test;
+"#,
+            expect![[r#"
+                de Default             builtin derive, defaults each field
+                de Clone, Copy         builtin derive, copies the value bitwise
+                de Eq                  builtin derive, marks `==` as an equivalence
+                de Eq, PartialOrd, Ord builtin derive, orders fields lexicographically
+                de Clone               builtin derive, clones each field
+                de PartialOrd          builtin derive, orders fields lexicographically
             "#]],
         );
     }
@@ -707,12 +2366,12 @@ mod derive {
 #[derive(serde::Serialize, PartialEq, $0)] struct Test;
 "#,
             expect![[r#"
-                de Default
-                de Clone, Copy
-                de Eq
-                de Eq, PartialOrd, Ord
-                de Clone
-                de PartialOrd
+                de Default             builtin derive, defaults each field
+                de Clone, Copy         builtin derive, copies the value bitwise
+                de Eq                  builtin derive, marks `==` as an equivalence
+                de Eq, PartialOrd, Ord builtin derive, orders fields lexicographically
+                de Clone               builtin derive, clones each field
+                de PartialOrd          builtin derive, orders fields lexicographically
             "#]],
         )
     }
@@ -725,12 +2384,12 @@ mod derive {
 #[derive($0 serde::Serialize, PartialEq)] struct Test;
 "#,
             expect![[r#"
-                de Default
-                de Clone, Copy
-                de Eq
-                de Eq, PartialOrd, Ord
-                de Clone
-                de PartialOrd
+                de Default             builtin derive, defaults each field
+                de Clone, Copy         builtin derive, copies the value bitwise
+                de Eq                  builtin derive, marks `==` as an equivalence
+                de Eq, PartialOrd, Ord builtin derive, orders fields lexicographically
+                de Clone               builtin derive, clones each field
+                de PartialOrd          builtin derive, orders fields lexicographically
             "#]],
         )
     }
@@ -763,7 +2422,7 @@ mod derive {
 #[derive(der$0)] struct Test;
 "#,
             expect![[r#"
-                de DeriveIdentity (use proc_macros::DeriveIdentity)
+                de DeriveIdentity (use proc_macros::DeriveIdentity) proc-macro derive
             "#]],
         );
         check_derive(
@@ -773,7 +2432,7 @@ use proc_macros::DeriveIdentity;
 #[derive(der$0)] struct Test;
 "#,
             expect![[r#"
-                de DeriveIdentity
+                de DeriveIdentity proc-macro derive
             "#]],
         );
     }
@@ -795,8 +2454,86 @@ use proc_macros::DeriveIdentity;
     }
 }
 
+mod link {
+    use super::*;
+
+    #[test]
+    fn link_keys() {
+        check(
+            r#"#[link(name = "foo", $0)] extern "C" {}"#,
+            expect![[r#"
+                ba kind = "…"
+                ba modifiers = "…"
+                ba wasm_import_module = "…"
+            "#]],
+        );
+        check(
+            r#"#[link(name = "foo", kind = $0)] extern "C" {}"#,
+            expect![[r#"
+                ba dylib
+                ba framework
+                ba raw-dylib
+                ba static
+            "#]],
+        );
+        check_edit(
+            "raw-dylib",
+            r#"#[link(name = "foo", kind = "ra$0")] extern "C" {}"#,
+            r#"#[link(name = "foo", kind = "raw-dylib")] extern "C" {}"#,
+        );
+    }
+
+    #[test]
+    fn import_name_type_on_windows_msvc() {
+        check(
+            r#"
+//- /main.rs crate:main cfg:target_os=windows,target_env=msvc
+#[link(name = "foo", kind = "raw-dylib", $0)] extern "C" {}
+"#,
+            expect![[r#"
+                ba modifiers = "…"
+                ba wasm_import_module = "…"
+                ba import_name_type = "…"
+            "#]],
+        );
+        check(
+            r#"
+//- /main.rs crate:main cfg:target_os=windows,target_env=msvc
+#[link(name = "foo", kind = "raw-dylib", import_name_type = $0)] extern "C" {}
+"#,
+            expect![[r#"
+                ba decorated
+                ba noprefix
+                ba undecorated
+            "#]],
+        );
+        // only raw-dylib links of msvc targets take an import name type
+        check(
+            r#"
+//- /main.rs crate:main cfg:target_os=windows,target_env=msvc
+#[link(name = "foo", kind = "dylib", $0)] extern "C" {}
+"#,
+            expect![[r#"
+                ba modifiers = "…"
+                ba wasm_import_module = "…"
+            "#]],
+        );
+        check(
+            r#"
+//- /main.rs crate:main cfg:target_os=windows,target_env=gnu
+#[link(name = "foo", kind = "raw-dylib", import_name_type = $0)] extern "C" {}
+"#,
+            expect![[]],
+        );
+    }
+}
+
 mod lint {
     use super::*;
+    use crate::{
+        tests::{check_edit_with_config, TEST_CONFIG},
+        CompletionConfig,
+    };
 
     #[test]
     fn lint_empty() {
@@ -807,6 +2544,62 @@ mod lint {
         )
     }
 
+    #[test]
+    fn lint_expect() {
+        check_edit(
+            "deprecated",
+            r#"#[expect($0)] struct Test;"#,
+            r#"#[expect(deprecated)] struct Test;"#,
+        )
+    }
+
+    #[test]
+    fn lint_reason() {
+        check_edit(
+            "reason",
+            r#"#[expect(deprecated, $0)] struct Test;"#,
+            r#"#[expect(deprecated, reason = "$0")] struct Test;"#,
+        );
+        let completions =
+            completion_list(r#"#[allow(deprecated, reason = "old", $0)] struct Test;"#);
+        assert!(!completions.contains("reason"));
+    }
+
+    #[test]
+    fn lints_covered_by_listed_groups() {
+        // the detail of the completion for `label`, if it is offered
+        let detail = |ra_fixture: &str, label: &str| {
+            completion_list(ra_fixture)
+                .lines()
+                .find(|it| it.split_whitespace().nth(1) == Some(label))
+                .map(|it| it.split_whitespace().skip(2).collect::<Vec<_>>().join(" "))
+        };
+        let fixture = r#"#[deny(warnings, unused, $0)] struct Test;"#;
+        assert_eq!(detail(fixture, "warnings"), None);
+        assert_eq!(detail(fixture, "unused_variables"), None);
+        assert_eq!(detail(fixture, "unsafe_code").as_deref(), Some("covered by `warnings`"));
+        let fixture = r#"#[deny(unused, $0)] struct Test;"#;
+        assert_eq!(detail(fixture, "unsafe_code").as_deref(), Some(""));
+
+        let fixture = r#"#[warn(clippy::all, clippy::$0)] struct Test;"#;
+        assert_eq!(detail(fixture, "needless_return"), None);
+        assert!(detail(fixture, "pedantic").is_some());
+        assert!(detail(fixture, "unwrap_used").is_some());
+    }
+
+    #[test]
+    fn feature_from_toolchain() {
+        check_edit_with_config(
+            CompletionConfig {
+                toolchain_features: vec!["some_new_feature".to_owned()],
+                ..TEST_CONFIG
+            },
+            "some_new_feature",
+            r#"#![feature(some_$0)]"#,
+            r#"#![feature(some_new_feature)]"#,
+        )
+    }
+
     #[test]
     fn lint_with_existing() {
         check_edit(
@@ -885,6 +2678,68 @@ mod lint {
     }
 }
 
+mod macro_use {
+    use super::*;
+
+    #[test]
+    fn exported_macros() {
+        check(
+            r#"
+//- /main.rs crate:main deps:dep
+#[macro_use($0)]
+extern crate dep;
+//- /dep.rs crate:dep
+#[macro_export]
+macro_rules! foo { () => {} }
+#[macro_export]
+macro_rules! bar { () => {} }
+macro_rules! private { () => {} }
+pub fn func() {}
+"#,
+            expect![[r#"
+                ma bar
+                ma foo
+            "#]],
+        );
+    }
+
+    #[test]
+    fn with_existing() {
+        check_edit(
+            "foo",
+            r#"
+//- /main.rs crate:main deps:dep
+#[macro_use(bar, $0)]
+extern crate dep;
+//- /dep.rs crate:dep
+#[macro_export]
+macro_rules! foo { () => {} }
+#[macro_export]
+macro_rules! bar { () => {} }
+"#,
+            r#"
+#[macro_use(bar, foo)]
+extern crate dep;
+"#,
+        );
+        check(
+            r#"
+//- /main.rs crate:main deps:dep
+#[macro_use(bar, $0)]
+extern crate dep;
+//- /dep.rs crate:dep
+#[macro_export]
+macro_rules! foo { () => {} }
+#[macro_export]
+macro_rules! bar { () => {} }
+"#,
+            expect![[r#"
+                ma foo
+            "#]],
+        );
+    }
+}
+
 mod repr {
     use super::*;
 
@@ -898,27 +2753,66 @@ mod repr {
         check_repr(r#"#[repr{$0)] struct Test;"#, expect![[]])
     }
 
+    #[test]
+    fn on_union() {
+        check_repr(
+            r#"#[repr($0)] union Test { a: u8, b: u16 }"#,
+            expect![[r#"
+            ba align(…)    alignment must be a power of two
+            ba packed      no padding, fields may be unaligned
+            ba transparent same layout as its single non-zero-sized field
+            ba C           C-compatible layout, fields keep their declaration order
+        "#]],
+        );
+    }
+
+    #[test]
+    fn unclosed_repr() {
+        check_repr(
+            r#"
+#[repr(C, $0
+struct Test;
+"#,
+            expect![[r#"
+                ba align(…) alignment must be a power of two
+                ba packed   no padding, fields may be unaligned
+                ba u8       enum discriminant is a `u8`
+                ba u16      enum discriminant is a `u16`
+                ba u32      enum discriminant is a `u32`
+                ba u64      enum discriminant is a `u64`
+                ba u128     enum discriminant is a `u128`
+                ba usize    enum discriminant is a `usize`
+                ba i8       enum discriminant is an `i8`
+                ba i16      enum discriminant is an `i16`
+                ba i32      enum discriminant is an `i32`
+                ba i64      enum discriminant is an `i64`
+                ba i128     enum discriminant is an `i128`
+                ba isize    enum discriminant is an `isize`
+            "#]],
+        );
+    }
+
     #[test]
     fn empty() {
         check_repr(
             r#"#[repr($0)] struct Test;"#,
             expect![[r#"
-                ba align($0)
-                ba packed
-                ba transparent
-                ba C
-                ba u8
-                ba u16
-                ba u32
-                ba u64
-                ba u128
-                ba usize
-                ba i8
-                ba i16
-                ba i32
-                ba i64
-                ba i28
-                ba isize
+                ba align(…)    alignment must be a power of two
+                ba packed      no padding, fields may be unaligned
+                ba transparent same layout as its single non-zero-sized field
+                ba C           C-compatible layout, fields keep their declaration order
+                ba u8          enum discriminant is a `u8`
+                ba u16         enum discriminant is a `u16`
+                ba u32         enum discriminant is a `u32`
+                ba u64         enum discriminant is a `u64`
+                ba u128        enum discriminant is a `u128`
+                ba usize       enum discriminant is a `usize`
+                ba i8          enum discriminant is an `i8`
+                ba i16         enum discriminant is an `i16`
+                ba i32         enum discriminant is an `i32`
+                ba i64         enum discriminant is an `i64`
+                ba i128        enum discriminant is an `i128`
+                ba isize       enum discriminant is an `isize`
             "#]],
         );
     }
@@ -928,25 +2822,33 @@ mod repr {
         check_repr(r#"#[repr(transparent, $0)] struct Test;"#, expect![[r#""#]]);
     }
 
+    #[test]
+    fn align_snippet() {
+        check_edit(
+            "align",
+            r#"#[repr($0)] struct Test;"#,
+            r#"#[repr(align(${0:8}))] struct Test;"#,
+        );
+    }
+
     #[test]
     fn align() {
         check_repr(
             r#"#[repr(align(1), $0)] struct Test;"#,
             expect![[r#"
-                ba transparent
-                ba C
-                ba u8
-                ba u16
-                ba u32
-                ba u64
-                ba u128
-                ba usize
-                ba i8
-                ba i16
-                ba i32
-                ba i64
-                ba i28
-                ba isize
+                ba C     C-compatible layout, fields keep their declaration order
+                ba u8    enum discriminant is a `u8`
+                ba u16   enum discriminant is a `u16`
+                ba u32   enum discriminant is a `u32`
+                ba u64   enum discriminant is a `u64`
+                ba u128  enum discriminant is a `u128`
+                ba usize enum discriminant is a `usize`
+                ba i8    enum discriminant is an `i8`
+                ba i16   enum discriminant is an `i16`
+                ba i32   enum discriminant is an `i32`
+                ba i64   enum discriminant is an `i64`
+                ba i128  enum discriminant is an `i128`
+                ba isize enum discriminant is an `isize`
             "#]],
         );
     }
@@ -956,20 +2858,41 @@ mod repr {
         check_repr(
             r#"#[repr(packed, $0)] struct Test;"#,
             expect![[r#"
-                ba transparent
-                ba C
-                ba u8
-                ba u16
-                ba u32
-                ba u64
-                ba u128
-                ba usize
-                ba i8
-                ba i16
-                ba i32
-                ba i64
-                ba i28
-                ba isize
+                ba C     C-compatible layout, fields keep their declaration order
+                ba u8    enum discriminant is a `u8`
+                ba u16   enum discriminant is a `u16`
+                ba u32   enum discriminant is a `u32`
+                ba u64   enum discriminant is a `u64`
+                ba u128  enum discriminant is a `u128`
+                ba usize enum discriminant is a `usize`
+                ba i8    enum discriminant is an `i8`
+                ba i16   enum discriminant is an `i16`
+                ba i32   enum discriminant is an `i32`
+                ba i64   enum discriminant is an `i64`
+                ba i128  enum discriminant is an `i128`
+                ba isize enum discriminant is an `isize`
+            "#]],
+        );
+    }
+
+    #[test]
+    fn conflicts_with_later_reprs() {
+        check_repr(
+            r#"#[repr($0, packed)] struct Test;"#,
+            expect![[r#"
+                ba C     C-compatible layout, fields keep their declaration order
+                ba u8    enum discriminant is a `u8`
+                ba u16   enum discriminant is a `u16`
+                ba u32   enum discriminant is a `u32`
+                ba u64   enum discriminant is a `u64`
+                ba u128  enum discriminant is a `u128`
+                ba usize enum discriminant is a `usize`
+                ba i8    enum discriminant is an `i8`
+                ba i16   enum discriminant is an `i16`
+                ba i32   enum discriminant is an `i32`
+                ba i64   enum discriminant is an `i64`
+                ba i128  enum discriminant is an `i128`
+                ba isize enum discriminant is an `isize`
             "#]],
         );
     }
@@ -979,20 +2902,20 @@ mod repr {
         check_repr(
             r#"#[repr(C, $0)] struct Test;"#,
             expect![[r#"
-                ba align($0)
-                ba packed
-                ba u8
-                ba u16
-                ba u32
-                ba u64
-                ba u128
-                ba usize
-                ba i8
-                ba i16
-                ba i32
-                ba i64
-                ba i28
-                ba isize
+                ba align(…) alignment must be a power of two
+                ba packed   no padding, fields may be unaligned
+                ba u8       enum discriminant is a `u8`
+                ba u16      enum discriminant is a `u16`
+                ba u32      enum discriminant is a `u32`
+                ba u64      enum discriminant is a `u64`
+                ba u128     enum discriminant is a `u128`
+                ba usize    enum discriminant is a `usize`
+                ba i8       enum discriminant is an `i8`
+                ba i16      enum discriminant is an `i16`
+                ba i32      enum discriminant is an `i32`
+                ba i64      enum discriminant is an `i64`
+                ba i128     enum discriminant is an `i128`
+                ba isize    enum discriminant is an `isize`
             "#]],
         );
     }
@@ -1002,10 +2925,126 @@ mod repr {
         check_repr(
             r#"#[repr(usize, $0)] struct Test;"#,
             expect![[r#"
-                ba align($0)
-                ba packed
-                ba C
+                ba align(…) alignment must be a power of two
+                ba packed   no padding, fields may be unaligned
+                ba C        C-compatible layout, fields keep their declaration order
+            "#]],
+        );
+    }
+
+    #[test]
+    fn transparent_on_multi_field_struct() {
+        check_repr(
+            r#"#[repr($0)] struct Test(u32, u32);"#,
+            expect![[r#"
+                ba align(…) alignment must be a power of two
+                ba packed   no padding, fields may be unaligned
+                ba C        C-compatible layout, fields keep their declaration order
+                ba u8       enum discriminant is a `u8`
+                ba u16      enum discriminant is a `u16`
+                ba u32      enum discriminant is a `u32`
+                ba u64      enum discriminant is a `u64`
+                ba u128     enum discriminant is a `u128`
+                ba usize    enum discriminant is a `usize`
+                ba i8       enum discriminant is an `i8`
+                ba i16      enum discriminant is an `i16`
+                ba i32      enum discriminant is an `i32`
+                ba i64      enum discriminant is an `i64`
+                ba i128     enum discriminant is an `i128`
+                ba isize    enum discriminant is an `isize`
+            "#]],
+        );
+    }
+
+    #[test]
+    fn transparent_on_newtype() {
+        check_edit(
+            "transparent",
+            r#"#[repr($0)] struct Test { field: u32 }"#,
+            r#"#[repr(transparent)] struct Test { field: u32 }"#,
+        );
+        check_edit(
+            "transparent",
+            r#"#[repr($0)] struct Test<T>(u32, T);"#,
+            r#"#[repr(transparent)] struct Test<T>(u32, T);"#,
+        );
+        check_edit(
+            "transparent",
+            r#"#[repr($0)] struct Test<T>(u32, PhantomData<T>);"#,
+            r#"#[repr(transparent)] struct Test<T>(u32, PhantomData<T>);"#,
+        );
+    }
+}
+
+mod target_feature {
+    use super::*;
+
+    #[test]
+    fn enable_key() {
+        check(
+            r#"#[target_feature($0)] fn f() {}"#,
+            expect![[r#"
+                ba enable = "…"
+            "#]],
+        );
+        check_edit(
+            "enable",
+            r#"#[target_feature($0)] fn f() {}"#,
+            r#"#[target_feature(enable = "$0")] fn f() {}"#,
+        );
+    }
+
+    #[test]
+    fn features_of_target() {
+        check(
+            r#"
+//- /main.rs crate:main cfg:target_arch=wasm32
+#[target_feature(enable = $0)]
+fn f() {}
+"#,
+            expect![[r#"
+                ba simd128
             "#]],
         );
+        check_edit(
+            "simd128",
+            r#"
+//- /main.rs crate:main cfg:target_arch=wasm32
+#[target_feature(enable = $0)]
+fn f() {}
+"#,
+            r#"
+#[target_feature(enable = "simd128")]
+fn f() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn signed_feature_in_string() {
+        check_edit(
+            "+sse4.1",
+            r#"
+//- /main.rs crate:main cfg:target_arch=x86_64
+#[target_feature(enable = "avx, +sse$0")]
+fn f() {}
+"#,
+            r#"
+#[target_feature(enable = "avx, sse4.1")]
+fn f() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn unknown_target() {
+        check(
+            r#"
+//- /main.rs crate:main cfg:target_arch=mips
+#[target_feature(enable = "$0")]
+fn f() {}
+"#,
+            expect![[r#""#]],
+        );
     }
 }
@@ -22,8 +22,12 @@ pub fn insert_ws_into(syn: SyntaxNode) -> SyntaxNode {
     let do_indent = |pos: fn(_) -> Position, token: &SyntaxToken, indent| {
         (pos(token.clone()), make::tokens::whitespace(&" ".repeat(2 * indent)))
     };
-    let do_ws = |pos: fn(_) -> Position, token: &SyntaxToken| {
-        (pos(token.clone()), make::tokens::single_space())
+    // Macro expansions may already separate some tokens by whitespace, don't double it
+    let do_ws = |pos: fn(_) -> Position, token: &SyntaxToken, neighbour: Option<SyntaxToken>| {
+        match neighbour {
+            Some(it) if it.kind() == WHITESPACE => None,
+            _ => Some((pos(token.clone()), make::tokens::single_space())),
+        }
     };
     let do_nl = |pos: fn(_) -> Position, token: &SyntaxToken| {
         (pos(token.clone()), make::tokens::single_newline())
@@ -31,7 +35,7 @@ pub fn insert_ws_into(syn: SyntaxNode) -> SyntaxNode {
 
     for event in syn.preorder_with_tokens() {
         let token = match event {
-            WalkEvent::Enter(NodeOrToken::Token(token)) => token,
+            WalkEvent::Enter(NodeOrToken::Token(token)) if token.kind() != WHITESPACE => token,
             WalkEvent::Leave(NodeOrToken::Node(node))
                 if matches!(node.kind(), ATTR | MATCH_ARM | STRUCT | ENUM | UNION | FN | IMPL) =>
             {
@@ -58,12 +62,12 @@ pub fn insert_ws_into(syn: SyntaxNode) -> SyntaxNode {
 
         match tok.kind() {
             k if is_text(k) && is_next(|it| !it.is_punct(), true) => {
-                mods.push(do_ws(after, tok));
+                mods.extend(do_ws(after, tok, tok.next_token()));
             }
             L_CURLY if is_next(|it| it != R_CURLY, true) => {
                 indent += 1;
                 if is_last(is_text, false) {
-                    mods.push(do_ws(before, tok));
+                    mods.extend(do_ws(before, tok, tok.prev_token()));
                 }
 
                 if indent > 0 {
@@ -86,10 +90,10 @@ pub fn insert_ws_into(syn: SyntaxNode) -> SyntaxNode {
                 mods.push(do_nl(after, tok));
             }
             LIFETIME_IDENT if is_next(|it| is_text(it), true) => {
-                mods.push(do_ws(after, tok));
+                mods.extend(do_ws(after, tok, tok.next_token()));
             }
             AS_KW | DYN_KW | IMPL_KW => {
-                mods.push(do_ws(after, tok));
+                mods.extend(do_ws(after, tok, tok.next_token()));
             }
             T![;] => {
                 if indent > 0 {
@@ -98,8 +102,8 @@ pub fn insert_ws_into(syn: SyntaxNode) -> SyntaxNode {
                 mods.push(do_nl(after, tok));
             }
             T![->] | T![=] | T![=>] => {
-                mods.push(do_ws(before, tok));
-                mods.push(do_ws(after, tok));
+                mods.extend(do_ws(before, tok, tok.prev_token()));
+                mods.extend(do_ws(after, tok, tok.next_token()));
             }
             _ => (),
         }
@@ -483,4 +483,14 @@ pub static SomeStatic: u8 = 10;
     "#,
         );
     }
+
+    #[test]
+    fn unsafe_no_mangle() {
+        check_diagnostics(
+            r#"
+#[unsafe(no_mangle)]
+pub extern "C" fn NoMangledFunction() {}
+    "#,
+        );
+    }
 }
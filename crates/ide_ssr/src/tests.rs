@@ -166,7 +166,7 @@ fn ssr_let_stmt_in_macro_match() {
             macro_rules! m1 { ($a:stmt) => {$a}; }
             fn f() {m1!{ let a = 0 };}"#,
         // FIXME: Whitespace is not part of the matched block
-        &["leta=0"],
+        &["let a=0"],
     );
 }
 
@@ -8,6 +8,7 @@ use syntax::{
 use test_utils::{bench, bench_fixture, skip_slow_tests};
 
 use crate::{
+    parse_to_token_tree,
     parser::{Op, RepeatKind, Separator},
     syntax_node_to_token_tree, DeclarativeMacro,
 };
@@ -47,6 +48,49 @@ fn benchmark_expand_macro_rules() {
     assert_eq!(hash, 69413);
 }
 
+#[test]
+fn benchmark_convert_flat_exprs() {
+    if skip_slow_tests() {
+        return;
+    }
+    let stmts: String =
+        (0..20_000).map(|i| format!("let _ = a{} + {} * b as u32 - c;\n", i, i)).collect();
+    let source_file = ast::SourceFile::parse(&format!("fn f() {{\n{}}}", stmts)).ok().unwrap();
+    let exprs: Vec<_> = source_file
+        .syntax()
+        .descendants()
+        .filter_map(ast::LetStmt::cast)
+        .filter_map(|it| it.initializer())
+        .collect();
+
+    let hash: usize = {
+        let _pt = bench("mbe convert flat exprs");
+        exprs.iter().map(|it| syntax_node_to_token_tree(it.syntax()).0.token_trees.len()).sum()
+    };
+    assert_eq!(hash, 180_000);
+}
+
+#[test]
+fn benchmark_raw_and_syntax_conversions() {
+    if skip_slow_tests() {
+        return;
+    }
+    let text = bench_fixture::glorious_old_parser();
+    let source_file = ast::SourceFile::parse(&text).tree();
+
+    let (raw, raw_map) = {
+        let _pt = bench("mbe convert lexed tokens");
+        parse_to_token_tree(&text).unwrap()
+    };
+    let (tt, map) = {
+        let _pt = bench("mbe convert syntax tree");
+        syntax_node_to_token_tree(source_file.syntax())
+    };
+    assert_eq!(raw.count(), 51_017);
+    assert!(raw == tt, "the conversions of the lexed tokens and the syntax tree differ");
+    assert!(raw_map == map, "the token maps of the conversions differ");
+}
+
 fn macro_rules_fixtures() -> FxHashMap<String, DeclarativeMacro> {
     macro_rules_fixtures_tt()
         .into_iter()
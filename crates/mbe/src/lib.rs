@@ -11,6 +11,7 @@ mod expander;
 mod syntax_bridge;
 mod tt_iter;
 mod to_parser_input;
+mod tt_utils;
 
 #[cfg(test)]
 mod benchmark;
@@ -29,11 +30,23 @@ pub use tt::{Delimiter, DelimiterKind, Punct};
 
 pub use crate::{
     syntax_bridge::{
-        parse_exprs_with_sep, parse_to_token_tree, syntax_node_to_token_tree,
-        syntax_node_to_token_tree_with_modifications, token_tree_to_syntax_node, SyntheticToken,
-        SyntheticTokenId,
+        parse_exprs_with_sep, parse_to_token_tree, parse_to_token_tree_edition,
+        parse_to_token_tree_lenient, syntax_node_to_token_tree, syntax_node_to_token_tree_in_range,
+        syntax_node_to_token_tree_with_config, syntax_node_to_token_tree_with_id_allocator,
+        syntax_node_to_token_tree_with_interner, syntax_node_to_token_tree_with_leaf_kinds,
+        syntax_node_to_token_tree_with_modifications, syntax_node_to_token_tree_with_next_id,
+        syntax_node_to_token_tree_with_punct_groups, syntax_node_to_token_trees_with_spans,
+        token_tree_to_syntax_node, token_tree_to_syntax_node_checked,
+        token_tree_to_syntax_node_with_config, token_tree_to_syntax_node_with_delimiter_spacing,
+        ConvertConfig, LiteralInterner, PunctGroup, ReconstructConfig, SequentialIds, SpanInfo,
+        SyntheticToken, SyntheticTokenId, TokenIdAllocator,
+    },
+    token_map::{subtree_source_range, DelimiterSpacing, TokenMap, TokenMapDiff},
+    tt_utils::{
+        canonicalize_spacing, extract_doc_comments, literal_kind, map_idents, pretty_print,
+        recombine_puncts, split_at_punct, token_tree_size, tt_structural_eq,
+        tt_to_comma_sep_idents, walk, LiteralKind, TokenTreeStats, TokenTreeVisitor,
     },
-    token_map::TokenMap,
 };
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -1,28 +1,233 @@
 //! Conversions between [`SyntaxNode`] and [`tt::TokenTree`].
 
-use rustc_hash::FxHashMap;
-use stdx::{always, non_empty_vec::NonEmptyVec};
+use std::mem;
+
+use rustc_hash::{FxHashMap, FxHashSet};
+use stdx::{always, never, non_empty_vec::NonEmptyVec};
 use syntax::{
     ast::{self, make::tokens::doc_comment},
-    AstToken, Parse, PreorderWithTokens, SmolStr, SyntaxElement, SyntaxKind,
+    AstNode, AstToken, Parse, PreorderWithTokens, SmolStr, SyntaxElement, SyntaxKind,
     SyntaxKind::*,
     SyntaxNode, SyntaxToken, SyntaxTreeBuilder, TextRange, TextSize, WalkEvent, T,
 };
-use tt::buffer::{Cursor, TokenBuffer};
+use tt::buffer::{Cursor, TokenBuffer, TokenTreeRef};
 
-use crate::{to_parser_input::to_parser_input, tt_iter::TtIter, TokenMap};
+use crate::{
+    subtree_source_range, to_parser_input::to_parser_input, tt_iter::TtIter, DelimiterSpacing,
+    TokenMap,
+};
 
 /// Convert the syntax node to a `TokenTree` (what macro
 /// will consume).
 pub fn syntax_node_to_token_tree(node: &SyntaxNode) -> (tt::Subtree, TokenMap) {
-    let (subtree, token_map, _) = syntax_node_to_token_tree_with_modifications(
+    let (subtree, token_map, _) = syntax_node_to_token_tree_with_next_id(node, 0);
+    (subtree, token_map)
+}
+
+/// Convert the syntax node to a `TokenTree` (what macro will consume), with the
+/// token ids starting at `next_id`.
+///
+/// Also returns the id following the last one used, so that the trees of
+/// several nodes can be converted one after the other without reusing ids.
+pub fn syntax_node_to_token_tree_with_next_id(
+    node: &SyntaxNode,
+    next_id: u32,
+) -> (tt::Subtree, TokenMap, u32) {
+    syntax_node_to_token_tree_with_modifications(
         node,
         Default::default(),
-        0,
+        next_id,
         Default::default(),
         Default::default(),
+    )
+}
+
+/// Convert the syntax node to a `TokenTree` (what macro will consume), with
+/// the optional behaviours of `config` enabled.
+pub fn syntax_node_to_token_tree_with_config(
+    node: &SyntaxNode,
+    config: ConvertConfig,
+) -> (tt::Subtree, TokenMap) {
+    syntax_node_to_token_tree_with_id_allocator(node, config, SequentialIds::default())
+}
+
+/// Convert the syntax node to a `TokenTree` (what macro will consume), with
+/// the token ids handed out by `ids` instead of sequentially.
+pub fn syntax_node_to_token_tree_with_id_allocator<A: TokenIdAllocator>(
+    node: &SyntaxNode,
+    config: ConvertConfig,
+    ids: A,
+) -> (tt::Subtree, TokenMap) {
+    convert_node(node, config, ids, &mut LiteralInterner::default(), None, None)
+}
+
+/// Convert the syntax node to a `TokenTree` (what macro will consume), sharing
+/// the text of long literals with the earlier conversions that used `literals`.
+pub fn syntax_node_to_token_tree_with_interner(
+    node: &SyntaxNode,
+    config: ConvertConfig,
+    literals: &mut LiteralInterner,
+) -> (tt::Subtree, TokenMap) {
+    convert_node(node, config, SequentialIds::default(), literals, None, None)
+}
+
+/// Convert the syntax node to a `TokenTree` (what macro will consume), along
+/// with the `SyntaxKind` of the token each leaf was converted from.
+///
+/// This keeps apart what the token tree no longer does, like a `BYTE_STRING`
+/// from a `STRING` or an `INT_NUMBER` from a `FLOAT_NUMBER`, without lexing the
+/// text of the leaves again. The leaves of desugared doc comments aren't recorded.
+pub fn syntax_node_to_token_tree_with_leaf_kinds(
+    node: &SyntaxNode,
+    config: ConvertConfig,
+) -> (tt::Subtree, TokenMap, FxHashMap<tt::TokenId, SyntaxKind>) {
+    let mut leaf_kinds = FxHashMap::default();
+    let (subtree, map) = convert_node(
+        node,
+        config,
+        SequentialIds::default(),
+        &mut LiteralInterner::default(),
+        Some(&mut leaf_kinds),
+        None,
     );
-    (subtree, token_map)
+    (subtree, map, leaf_kinds)
+}
+
+/// A multi-char operator, like `==` or `..=`, that was split into `len`
+/// consecutive single char `tt::Punct`s.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PunctGroup {
+    /// The kind of the operator token, e.g. `T![==]`.
+    pub kind: SyntaxKind,
+    /// The number of puncts the operator was split into.
+    pub len: usize,
+}
+
+/// Convert the syntax node to a `TokenTree` (what macro will consume), along
+/// with the operators that were split into several puncts, keyed by the id of
+/// their first punct.
+///
+/// `tt::Punct` is a single char by design, so the token tree itself is the
+/// usual one. The groups are for consumers of the input as written, like
+/// formatters; unlike [`crate::recombine_puncts`] they follow the parsed tokens
+/// rather than the spacing. They are not compatible with macro expansion: the
+/// ids only refer to this conversion, and the rules of a macro glue and split
+/// puncts as they see fit, so expanded trees can't be grouped with them.
+pub fn syntax_node_to_token_tree_with_punct_groups(
+    node: &SyntaxNode,
+    config: ConvertConfig,
+) -> (tt::Subtree, TokenMap, FxHashMap<tt::TokenId, PunctGroup>) {
+    let mut punct_groups = FxHashMap::default();
+    let (subtree, map) = convert_node(
+        node,
+        config,
+        SequentialIds::default(),
+        &mut LiteralInterner::default(),
+        None,
+        Some(&mut punct_groups),
+    );
+    (subtree, map, punct_groups)
+}
+
+fn convert_node<A: TokenIdAllocator>(
+    node: &SyntaxNode,
+    config: ConvertConfig,
+    ids: A,
+    literals: &mut LiteralInterner,
+    leaf_kinds: Option<&mut FxHashMap<tt::TokenId, SyntaxKind>>,
+    punct_groups: Option<&mut FxHashMap<tt::TokenId, PunctGroup>>,
+) -> (tt::Subtree, TokenMap) {
+    let global_offset =
+        if config.absolute_ranges { TextSize::default() } else { node.text_range().start() };
+    let mut c = Convertor::new(
+        node,
+        node.text_range(),
+        global_offset,
+        Default::default(),
+        ids,
+        Default::default(),
+        Default::default(),
+    );
+    if config.record_item_ranges {
+        for item in node.children().filter(|it| ast::Item::can_cast(it.kind())) {
+            c.id_alloc.record_item(item.text_range());
+        }
+    }
+    let subtree = convert_tokens(&mut c, &config, literals, leaf_kinds, punct_groups);
+    c.id_alloc.map.shrink_to_fit();
+    (subtree, c.id_alloc.map)
+}
+
+/// The source of a token tree, enough to build a `proc_macro2`-like span
+/// without going through the macro file ids of `hir_expand`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SpanInfo {
+    /// Range in the file the converted node belongs to.
+    pub range: TextRange,
+    /// Whatever id the caller uses for that file, passed through as is.
+    pub file_id: Option<u32>,
+}
+
+/// Convert the syntax node to the `TokenTree`s it consists of, each paired with
+/// the range it was converted from. A subtree spans its delimiters, the spans of
+/// the tokens nested in it aren't reported.
+pub fn syntax_node_to_token_trees_with_spans(
+    node: &SyntaxNode,
+    file_id: Option<u32>,
+) -> Vec<(tt::TokenTree, SpanInfo)> {
+    let config = ConvertConfig { absolute_ranges: true, ..ConvertConfig::default() };
+    let (subtree, map) = syntax_node_to_token_tree_with_config(node, config);
+    let trees = match subtree.delimiter {
+        Some(_) => vec![tt::TokenTree::Subtree(subtree)],
+        None => subtree.token_trees,
+    };
+    let mut next_range = TextRange::empty(node.text_range().end());
+    let mut res: Vec<_> = trees
+        .into_iter()
+        .rev()
+        .map(|tree| {
+            let range = match &tree {
+                // only delimiters have several ranges to pick from by kind
+                tt::TokenTree::Leaf(leaf) => map.first_range_by_token(leaf.id(), IDENT),
+                tt::TokenTree::Subtree(subtree) => subtree_source_range(subtree, &map),
+            };
+            // the `#` of a desugared doc comment has no source, share the comment's
+            let range = range.unwrap_or(next_range);
+            next_range = range;
+            (tree, SpanInfo { range, file_id })
+        })
+        .collect();
+    res.reverse();
+    res
+}
+
+/// Convert the tokens of the syntax node lying within `range` to a `TokenTree`,
+/// e.g. a single argument of a call without the rest of the argument list.
+///
+/// Tokens straddling a boundary of `range` are left out, and the ranges in the
+/// returned `TokenMap` are relative to the start of `range`.
+pub fn syntax_node_to_token_tree_in_range(
+    node: &SyntaxNode,
+    range: TextRange,
+) -> (tt::Subtree, TokenMap) {
+    let mut c = Convertor::new(
+        node,
+        range,
+        range.start(),
+        Default::default(),
+        SequentialIds::default(),
+        Default::default(),
+        Default::default(),
+    );
+    let subtree = convert_tokens(
+        &mut c,
+        &ConvertConfig::default(),
+        &mut LiteralInterner::default(),
+        None,
+        None,
+    );
+    c.id_alloc.map.shrink_to_fit();
+    (subtree, c.id_alloc.map)
 }
 
 /// Convert the syntax node to a `TokenTree` (what macro will consume)
@@ -35,21 +240,139 @@ pub fn syntax_node_to_token_tree_with_modifications(
     append: FxHashMap<SyntaxNode, Vec<SyntheticToken>>,
 ) -> (tt::Subtree, TokenMap, u32) {
     let global_offset = node.text_range().start();
-    let mut c = Convertor::new(node, global_offset, existing_token_map, next_id, replace, append);
-    let subtree = convert_tokens(&mut c);
+    let ids = SequentialIds { next_id };
+    let mut c = Convertor::new(
+        node,
+        node.text_range(),
+        global_offset,
+        existing_token_map,
+        ids,
+        replace,
+        append,
+    );
+    let subtree = convert_tokens(
+        &mut c,
+        &ConvertConfig::default(),
+        &mut LiteralInterner::default(),
+        None,
+        None,
+    );
     c.id_alloc.map.shrink_to_fit();
     always!(c.replace.is_empty(), "replace: {:?}", c.replace);
     always!(c.append.is_empty(), "append: {:?}", c.append);
-    (subtree, c.id_alloc.map, c.id_alloc.next_id)
+    (subtree, c.id_alloc.map, c.id_alloc.ids.next_id)
+}
+
+/// Optional behaviours of the conversion to `TokenTree`, all disabled by default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ConvertConfig {
+    /// Record the range of every `COMMENT` token in [`TokenMap::comment_ranges`].
+    ///
+    /// Comments are still not emitted as leaves, this only keeps track of
+    /// where they were so that range mapping can skip over them.
+    pub record_comment_ranges: bool,
+    /// Record the shape and placement of every doc comment that gets desugared
+    /// into a `#[doc = "..."]` attribute, see [`TokenMap::doc_comment_kind`].
+    ///
+    /// This doesn't affect the emitted token tree.
+    pub record_doc_comment_kinds: bool,
+    /// Store the ranges in the [`TokenMap`] as offsets into the whole file
+    /// rather than relative to the start of the converted node.
+    ///
+    /// This saves consumers working at file scope from adding the node's offset
+    /// back on every lookup, but the map then has to be rebuilt whenever text
+    /// before the node changes, even if the node itself didn't.
+    pub absolute_ranges: bool,
+    /// Record the ranges of the items directly inside the converted node, see
+    /// [`TokenMap::item_ranges`], so that their boundaries can be told apart in
+    /// the flat token trees, e.g. when converting a whole file.
+    ///
+    /// This also keeps the result from being collapsed into its only subtree
+    /// when the node consists of a single delimited group.
+    pub record_item_ranges: bool,
+    /// Record the `SyntaxKind` of every keyword, see [`TokenMap::keyword_kind`].
+    ///
+    /// Keywords are still emitted as `tt::Ident`s, which is what macros expect,
+    /// this only spares consumers like highlighting from lexing them again.
+    pub record_keyword_kinds: bool,
+    /// Record whether there's whitespace right inside the delimiters, see
+    /// [`TokenMap::delimiter_spacing`], so that
+    /// [`token_tree_to_syntax_node_with_delimiter_spacing`] can put it back.
+    pub record_delimiter_spacing: bool,
+}
+
+/// Hands out the text of literals longer than a threshold from a shared
+/// allocation, so that converting the same huge literal, as found in generated
+/// code, over and over doesn't allocate a copy of it every time.
+///
+/// Shorter literals are stored inline by `SmolStr` anyway, or are cheap enough
+/// to copy. The default interner shares nothing.
+#[derive(Debug)]
+pub struct LiteralInterner {
+    min_len: usize,
+    texts: FxHashSet<SmolStr>,
+}
+
+impl Default for LiteralInterner {
+    fn default() -> Self {
+        LiteralInterner::new(usize::MAX)
+    }
+}
+
+impl LiteralInterner {
+    /// Shares the text of literals of at least `min_len` bytes.
+    pub fn new(min_len: usize) -> LiteralInterner {
+        LiteralInterner { min_len, texts: FxHashSet::default() }
+    }
+
+    fn intern(&mut self, text: &str) -> SmolStr {
+        if text.len() < self.min_len {
+            return SmolStr::new(text);
+        }
+        if let Some(it) = self.texts.get(text) {
+            return it.clone();
+        }
+        let it = SmolStr::new(text);
+        self.texts.insert(it.clone());
+        it
+    }
+}
+
+/// Decides which [`tt::TokenId`] each converted token and delimiter gets.
+///
+/// The conversions allocate ids with [`SequentialIds`] unless told otherwise,
+/// other allocators are mostly useful to get ids that are stable across runs.
+pub trait TokenIdAllocator {
+    /// Returns the id for the token, or the opening delimiter, at `relative_range`,
+    /// which is relative to the start of the converted node unless
+    /// [`ConvertConfig::absolute_ranges`] is set.
+    fn alloc(&mut self, relative_range: TextRange) -> tt::TokenId;
+}
+
+/// Allocates consecutive ids, starting at `next_id`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SequentialIds {
+    pub next_id: u32,
+}
+
+impl TokenIdAllocator for SequentialIds {
+    fn alloc(&mut self, _relative_range: TextRange) -> tt::TokenId {
+        let token_id = tt::TokenId(self.next_id);
+        self.next_id += 1;
+        token_id
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SyntheticTokenId(pub u32);
 
 #[derive(Debug, Clone)]
 pub struct SyntheticToken {
     pub kind: SyntaxKind,
     pub text: SmolStr,
+    /// Must lie within the range of the node being converted, ranges starting
+    /// before it are clamped to its start.
     pub range: TextRange,
     pub id: SyntheticTokenId,
 }
@@ -69,6 +392,58 @@ pub struct SyntheticToken {
 pub fn token_tree_to_syntax_node(
     tt: &tt::Subtree,
     entry_point: parser::TopEntryPoint,
+) -> (Parse<SyntaxNode>, TokenMap) {
+    token_tree_to_syntax_node_with_config(tt, entry_point, ReconstructConfig::default())
+}
+
+/// Optional behaviours of [`token_tree_to_syntax_node_with_config`], all
+/// disabled by default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReconstructConfig {
+    /// Separate the `>`s that close nested generic argument lists, producing
+    /// `Vec<Vec<u8> >` rather than `Vec<Vec<u8>>`.
+    ///
+    /// Both are valid Rust, this is for consumers of the text that predate the
+    /// latter. Shift operators are never split.
+    pub split_closing_angles: bool,
+    /// Put a space after every `;` followed by more tokens of the same subtree,
+    /// producing `a; b;` rather than `a;b;`.
+    ///
+    /// Off by default, other parts of rust-analyzer assume a `;` is the last
+    /// token of the text and nothing is inserted after it.
+    pub space_after_semicolon: bool,
+}
+
+/// Like [`token_tree_to_syntax_node`], with the optional behaviours of `config`
+/// enabled.
+pub fn token_tree_to_syntax_node_with_config(
+    tt: &tt::Subtree,
+    entry_point: parser::TopEntryPoint,
+    config: ReconstructConfig,
+) -> (Parse<SyntaxNode>, TokenMap) {
+    reconstruct(tt, entry_point, config, None)
+}
+
+/// Like [`token_tree_to_syntax_node_with_config`], additionally putting a space
+/// right inside the delimiters that had whitespace there in the source, going by
+/// the map `tt` was converted with (see [`ConvertConfig::record_delimiter_spacing`]).
+///
+/// This keeps `vec![ 1, 2 ]` from becoming `vec![1, 2]`. Any amount of
+/// whitespace, newlines included, comes back as a single space.
+pub fn token_tree_to_syntax_node_with_delimiter_spacing(
+    tt: &tt::Subtree,
+    entry_point: parser::TopEntryPoint,
+    config: ReconstructConfig,
+    source_map: &TokenMap,
+) -> (Parse<SyntaxNode>, TokenMap) {
+    reconstruct(tt, entry_point, config, Some(source_map))
+}
+
+fn reconstruct(
+    tt: &tt::Subtree,
+    entry_point: parser::TopEntryPoint,
+    config: ReconstructConfig,
+    source_map: Option<&TokenMap>,
 ) -> (Parse<SyntaxNode>, TokenMap) {
     let buffer = match tt {
         tt::Subtree { delimiter: None, token_trees } => {
@@ -78,7 +453,7 @@ pub fn token_tree_to_syntax_node(
     };
     let parser_input = to_parser_input(&buffer);
     let parser_output = entry_point.parse(&parser_input);
-    let mut tree_sink = TtTreeSink::new(buffer.begin());
+    let mut tree_sink = TtTreeSink::new(buffer.begin(), config, source_map);
     for event in parser_output.iter() {
         match event {
             parser::Step::Token { kind, n_input_tokens: n_raw_tokens } => {
@@ -93,25 +468,142 @@ pub fn token_tree_to_syntax_node(
     (parse, range_map)
 }
 
+/// Like [`token_tree_to_syntax_node`], but additionally re-lexes the text of
+/// the produced tree and checks that it yields the same tokens as `tt`.
+///
+/// Returns an error describing the first mismatching token, which usually
+/// means that the reconstructed text glued two tokens together.
+pub fn token_tree_to_syntax_node_checked(
+    tt: &tt::Subtree,
+    entry_point: parser::TopEntryPoint,
+) -> Result<(Parse<SyntaxNode>, TokenMap), String> {
+    let (parse, token_map) = token_tree_to_syntax_node(tt, entry_point);
+
+    let text = parse.syntax_node().to_string();
+    let lexed = parser::LexedStr::new(&text);
+    let mut actual = (0..lexed.len()).map(|i| lexed.kind(i)).filter(|kind| !kind.is_trivia());
+    let mut expected = Vec::new();
+    expected_token_kinds(tt, &mut expected);
+
+    for (idx, &expected) in expected.iter().enumerate() {
+        match actual.next() {
+            Some(actual) if actual == expected => (),
+            Some(actual) => {
+                return Err(format!(
+                    "token #{} re-lexed as {:?}, expected {:?}: {:?}",
+                    idx, actual, expected, text
+                ))
+            }
+            None => {
+                return Err(format!("token #{} missing, expected {:?}: {:?}", idx, expected, text))
+            }
+        }
+    }
+    if let Some(actual) = actual.next() {
+        return Err(format!("unexpected trailing token {:?}: {:?}", actual, text));
+    }
+    Ok((parse, token_map))
+}
+
+/// Computes the `SyntaxKind`s the lexer should produce for the given subtree.
+fn expected_token_kinds(subtree: &tt::Subtree, acc: &mut Vec<SyntaxKind>) {
+    let (open, close) = match subtree.delimiter_kind() {
+        Some(tt::DelimiterKind::Parenthesis) => (Some(T!['(']), Some(T![')'])),
+        Some(tt::DelimiterKind::Brace) => (Some(T!['{']), Some(T!['}'])),
+        Some(tt::DelimiterKind::Bracket) => (Some(T!['[']), Some(T![']'])),
+        None => (None, None),
+    };
+    acc.extend(open);
+    let mut iter = subtree.token_trees.iter().peekable();
+    while let Some(tt) = iter.next() {
+        match tt {
+            tt::TokenTree::Leaf(tt::Leaf::Punct(punct)) => {
+                if punct.char == '\'' {
+                    if let Some(tt::TokenTree::Leaf(tt::Leaf::Ident(_))) = iter.peek() {
+                        iter.next();
+                        acc.push(LIFETIME_IDENT);
+                        continue;
+                    }
+                }
+                acc.extend(SyntaxKind::from_char(punct.char));
+            }
+            tt::TokenTree::Leaf(tt::Leaf::Ident(ident)) => acc.push(match &*ident.text {
+                "_" => T![_],
+                text => SyntaxKind::from_keyword(text).unwrap_or(IDENT),
+            }),
+            tt::TokenTree::Leaf(tt::Leaf::Literal(lit)) => {
+                let text = match lit.text.strip_prefix('-') {
+                    Some(text) => {
+                        acc.push(T![-]);
+                        text
+                    }
+                    None => &lit.text,
+                };
+                acc.extend(parser::LexedStr::single_token(text).map(|(kind, _)| kind));
+            }
+            tt::TokenTree::Subtree(subtree) => expected_token_kinds(subtree, acc),
+        }
+    }
+    acc.extend(close);
+}
+
 /// Convert a string to a `TokenTree`
+///
+/// Keywords and identifiers both become `tt::Ident`s, edition dependent keywords
+/// like `async` or `dyn` are only told apart when the tree is parsed again. The
+/// lexing itself follows the latest edition, use [`parse_to_token_tree_edition`]
+/// for text from an older one.
 pub fn parse_to_token_tree(text: &str) -> Option<(tt::Subtree, TokenMap)> {
-    let lexed = parser::LexedStr::new(text);
+    parse_to_token_tree_edition(text, parser::Edition::LATEST)
+}
+
+/// Like [`parse_to_token_tree`], but lexes the text as `edition` does: before
+/// 2021, `'r#foo` is the lifetime `'r` followed by `#` and `foo` rather than a
+/// raw lifetime.
+pub fn parse_to_token_tree_edition(
+    text: &str,
+    edition: parser::Edition,
+) -> Option<(tt::Subtree, TokenMap)> {
+    let lexed = parser::LexedStr::new_with_edition(text, edition);
     if lexed.errors().next().is_some() {
         return None;
     }
+    Some(convert_lexed(lexed))
+}
+
+/// Like [`parse_to_token_tree`], but tolerates the unterminated string, char
+/// and block comment tokens that are common while the user is still typing.
+/// Such a token spans to the end of the text and is converted as is.
+pub fn parse_to_token_tree_lenient(text: &str) -> Option<(tt::Subtree, TokenMap)> {
+    let lexed = parser::LexedStr::new(text);
+    let only_unterminated = lexed
+        .errors()
+        .all(|(idx, _)| matches!(lexed.kind(idx), STRING | BYTE_STRING | CHAR | BYTE | COMMENT));
+    if !only_unterminated {
+        return None;
+    }
+    Some(convert_lexed(lexed))
+}
 
+fn convert_lexed(lexed: parser::LexedStr) -> (tt::Subtree, TokenMap) {
     let mut conv = RawConvertor {
         lexed,
         pos: 0,
         id_alloc: TokenIdAlloc {
             map: Default::default(),
             global_offset: TextSize::default(),
-            next_id: 0,
+            ids: SequentialIds::default(),
         },
     };
 
-    let subtree = convert_tokens(&mut conv);
-    Some((subtree, conv.id_alloc.map))
+    let subtree = convert_tokens(
+        &mut conv,
+        &ConvertConfig::default(),
+        &mut LiteralInterner::default(),
+        None,
+        None,
+    );
+    (subtree, conv.id_alloc.map)
 }
 
 /// Split token tree with separate expr: $($e:expr)SEP*
@@ -148,11 +640,18 @@ pub fn parse_exprs_with_sep(tt: &tt::Subtree, sep: char) -> Vec<tt::Subtree> {
     res
 }
 
-fn convert_tokens<C: TokenConvertor>(conv: &mut C) -> tt::Subtree {
+fn convert_tokens<C: TokenConvertor>(
+    conv: &mut C,
+    config: &ConvertConfig,
+    literals: &mut LiteralInterner,
+    mut leaf_kinds: Option<&mut FxHashMap<tt::TokenId, SyntaxKind>>,
+    mut punct_groups: Option<&mut FxHashMap<tt::TokenId, PunctGroup>>,
+) -> tt::Subtree {
     struct StackEntry {
         subtree: tt::Subtree,
         idx: usize,
         open_range: TextRange,
+        space_after_open: bool,
     }
 
     let entry = StackEntry {
@@ -160,8 +659,16 @@ fn convert_tokens<C: TokenConvertor>(conv: &mut C) -> tt::Subtree {
         // never used (delimiter is `None`)
         idx: !0,
         open_range: TextRange::empty(TextSize::of('.')),
+        space_after_open: false,
     };
+    // Flat inputs, like most `$e:expr` fragments, never grow the stack. Converting
+    // them in a separate loop that bypasses it doesn't pay off though, the time
+    // goes into walking the tokens and filling the `TokenMap` (see
+    // `benchmark_convert_flat_exprs`).
     let mut stack = NonEmptyVec::new(entry);
+    // the puncts still to come of the operator being split
+    let mut group_remaining = 0;
+    let mut prev_is_whitespace = false;
 
     loop {
         let StackEntry { subtree, .. } = stack.last_mut();
@@ -171,23 +678,48 @@ fn convert_tokens<C: TokenConvertor>(conv: &mut C) -> tt::Subtree {
             None => break,
         };
         let synth_id = token.synthetic_id(&conv);
+        if synth_id.is_some() {
+            conv.id_alloc().map.count_synthetic_token();
+        }
 
         let kind = token.kind(&conv);
+        let after_whitespace = mem::replace(&mut prev_is_whitespace, kind == WHITESPACE);
         if kind == COMMENT {
+            if config.record_comment_ranges {
+                conv.id_alloc().record_comment(range);
+            }
             if let Some(tokens) = conv.convert_doc_comment(&token) {
                 // FIXME: There has to be a better way to do this
                 // Add the comments token id to the converted doc string
                 let id = conv.id_alloc().alloc(range, synth_id);
-                result.extend(tokens.into_iter().map(|mut tt| {
+                if config.record_doc_comment_kinds {
+                    let comment_kind = ast::CommentKind::from_text(&token.to_text(conv));
+                    conv.id_alloc().map.insert_doc_comment_kind(id, comment_kind);
+                }
+                for mut tt in tokens {
                     if let tt::TokenTree::Subtree(sub) = &mut tt {
-                        if let Some(tt::TokenTree::Leaf(tt::Leaf::Literal(lit))) =
-                            sub.token_trees.get_mut(2)
-                        {
-                            lit.id = id
+                        let lit = sub.token_trees.iter_mut().find_map(|it| match it {
+                            tt::TokenTree::Leaf(tt::Leaf::Literal(lit)) => Some(lit),
+                            _ => None,
+                        });
+                        match lit {
+                            Some(lit) => lit.id = id,
+                            None => never!("doc comment without a literal: {:?}", sub),
                         }
+                        // Map the brackets to the first and last character of the comment
+                        let inner = tt::Subtree {
+                            delimiter: None,
+                            token_trees: mem::take(&mut sub.token_trees),
+                        };
+                        *sub = wrap_in_delimiter(
+                            inner,
+                            tt::DelimiterKind::Bracket,
+                            conv.id_alloc(),
+                            range,
+                        );
                     }
-                    tt
-                }));
+                    result.push(tt);
+                }
             }
             continue;
         }
@@ -206,6 +738,15 @@ fn convert_tokens<C: TokenConvertor>(conv: &mut C) -> tt::Subtree {
                 if kind == expected {
                     if let Some(entry) = stack.pop() {
                         conv.id_alloc().close_delim(entry.idx, Some(range));
+                        if config.record_delimiter_spacing
+                            && (entry.space_after_open || after_whitespace)
+                        {
+                            let spacing = DelimiterSpacing {
+                                after_open: entry.space_after_open,
+                                before_close: after_whitespace,
+                            };
+                            conv.id_alloc().map.insert_delimiter_spacing(delim.id, spacing);
+                        }
                         stack.last_mut().subtree.token_trees.push(entry.subtree.into());
                     }
                     continue;
@@ -223,7 +764,9 @@ fn convert_tokens<C: TokenConvertor>(conv: &mut C) -> tt::Subtree {
                 let mut subtree = tt::Subtree::default();
                 let (id, idx) = conv.id_alloc().open_delim(range);
                 subtree.delimiter = Some(tt::Delimiter { id, kind });
-                stack.push(StackEntry { subtree, idx, open_range: range });
+                let space_after_open =
+                    matches!(conv.peek(), Some(next) if next.kind(conv) == WHITESPACE);
+                stack.push(StackEntry { subtree, idx, open_range: range, space_after_open });
                 continue;
             }
 
@@ -246,8 +789,20 @@ fn convert_tokens<C: TokenConvertor>(conv: &mut C) -> tt::Subtree {
                     panic!("Token from lexer must be single char: token = {:#?}", token);
                 }
             };
-            tt::Leaf::from(tt::Punct { char, spacing, id: conv.id_alloc().alloc(range, synth_id) })
-                .into()
+            let id = conv.id_alloc().alloc(range, synth_id);
+            if let Some(punct_groups) = &mut punct_groups {
+                if group_remaining > 0 {
+                    group_remaining -= 1;
+                } else {
+                    // only the tokens of syntax nodes span several chars
+                    let len = token.with_text(conv, |text| text.len());
+                    if len > 1 {
+                        punct_groups.insert(id, PunctGroup { kind, len });
+                        group_remaining = len - 1;
+                    }
+                }
+            }
+            tt::Leaf::from(tt::Punct { char, spacing, id }).into()
         } else {
             macro_rules! make_leaf {
                 ($i:ident) => {
@@ -260,7 +815,11 @@ fn convert_tokens<C: TokenConvertor>(conv: &mut C) -> tt::Subtree {
                 IDENT => make_leaf!(Ident),
                 UNDERSCORE => make_leaf!(Ident),
                 k if k.is_keyword() => make_leaf!(Ident),
-                k if k.is_literal() => make_leaf!(Literal),
+                k if k.is_literal() => tt::Literal {
+                    id: conv.id_alloc().alloc(range, synth_id),
+                    text: token.with_text(conv, |text| literals.intern(text)),
+                }
+                .into(),
                 LIFETIME_IDENT => {
                     let char_unit = TextSize::of('\'');
                     let r = TextRange::at(range.start(), char_unit);
@@ -269,21 +828,34 @@ fn convert_tokens<C: TokenConvertor>(conv: &mut C) -> tt::Subtree {
                         spacing: tt::Spacing::Joint,
                         id: conv.id_alloc().alloc(r, synth_id),
                     });
+                    if let Some(leaf_kinds) = &mut leaf_kinds {
+                        leaf_kinds.insert(apostrophe.id(), kind);
+                    }
                     result.push(apostrophe.into());
 
+                    // a raw lifetime keeps its `r#` in the ident, like a raw identifier
                     let r = TextRange::at(range.start() + char_unit, range.len() - char_unit);
                     let ident = tt::Leaf::from(tt::Ident {
                         text: SmolStr::new(&token.to_text(conv)[1..]),
                         id: conv.id_alloc().alloc(r, synth_id),
                     });
+                    if let Some(leaf_kinds) = &mut leaf_kinds {
+                        leaf_kinds.insert(ident.id(), kind);
+                    }
                     result.push(ident.into());
                     continue;
                 }
                 _ => continue,
             };
+            if config.record_keyword_kinds && kind.is_keyword() {
+                conv.id_alloc().map.insert_keyword_kind(leaf.id(), kind);
+            }
 
             leaf.into()
         };
+        if let (Some(leaf_kinds), tt::TokenTree::Leaf(leaf)) = (&mut leaf_kinds, &tt) {
+            leaf_kinds.insert(leaf.id(), kind);
+        }
         result.push(tt);
     }
 
@@ -309,6 +881,9 @@ fn convert_tokens<C: TokenConvertor>(conv: &mut C) -> tt::Subtree {
     }
 
     let subtree = stack.into_last().subtree;
+    if config.record_item_ranges {
+        return subtree;
+    }
     if let [tt::TokenTree::Subtree(first)] = &*subtree.token_trees {
         first.clone()
     } else {
@@ -324,15 +899,46 @@ fn doc_comment_text(comment: &ast::Comment) -> SmolStr {
     let prefix_len = comment.prefix().len();
     let mut text = &comment.text()[prefix_len..];
 
-    // Remove ending "*/"
-    if comment.kind().shape == ast::CommentShape::Block {
-        text = &text[0..text.len() - 2];
+    // Remove ending "*/", or the `\r` of a CRLF line ending
+    match comment.kind().shape {
+        ast::CommentShape::Block => text = &text[0..text.len() - 2],
+        ast::CommentShape::Line => text = text.strip_suffix('\r').unwrap_or(text),
     }
 
+    // Normalize CRLF line endings to LF, like rustc does
+    let text = text.replace("\r\n", "\n");
+
     // Quote the string
-    // Note that `tt::Literal` expect an escaped string
-    let text = format!("\"{}\"", text.escape_debug());
-    text.into()
+    // Note that `tt::Literal` expect an escaped string, see the
+    // `doc_comment_escaping_round_trips` test
+    let literal = format!("\"{}\"", text.escape_debug());
+    #[cfg(debug_assertions)]
+    check_doc_literal(&literal, &text);
+    literal.into()
+}
+
+/// Checks that `literal` lexes as a single string literal whose value is `text`.
+#[cfg(debug_assertions)]
+fn check_doc_literal(literal: &str, text: &str) {
+    let round_trips = match parser::LexedStr::single_token(literal) {
+        Some((STRING, None)) => {
+            let mut value = String::with_capacity(text.len());
+            let mut has_error = false;
+            rustc_lexer::unescape::unescape_literal(
+                &literal[1..literal.len() - 1],
+                rustc_lexer::unescape::Mode::Str,
+                &mut |_, c| match c {
+                    Ok(c) => value.push(c),
+                    Err(_) => has_error = true,
+                },
+            );
+            !has_error && value == text
+        }
+        _ => false,
+    };
+    if !round_trips {
+        tracing::warn!("doc comment {:?} was escaped to {:?}", text, literal);
+    }
 }
 
 fn convert_doc_comment(token: &syntax::SyntaxToken) -> Option<Vec<tt::TokenTree>> {
@@ -382,21 +988,20 @@ fn convert_doc_comment(token: &syntax::SyntaxToken) -> Option<Vec<tt::TokenTree>
     }
 }
 
-struct TokenIdAlloc {
+struct TokenIdAlloc<A> {
     map: TokenMap,
     global_offset: TextSize,
-    next_id: u32,
+    ids: A,
 }
 
-impl TokenIdAlloc {
+impl<A: TokenIdAllocator> TokenIdAlloc<A> {
     fn alloc(
         &mut self,
         absolute_range: TextRange,
         synthetic_id: Option<SyntheticTokenId>,
     ) -> tt::TokenId {
-        let relative_range = absolute_range - self.global_offset;
-        let token_id = tt::TokenId(self.next_id);
-        self.next_id += 1;
+        let relative_range = self.relative_range(absolute_range);
+        let token_id = self.ids.alloc(relative_range);
         self.map.insert(token_id, relative_range);
         if let Some(id) = synthetic_id {
             self.map.insert_synthetic(token_id, id);
@@ -404,34 +1009,79 @@ impl TokenIdAlloc {
         token_id
     }
 
+    /// Makes `absolute_range` relative to the converted node, clamping (bogus
+    /// synthetic) ranges that start before the node instead of underflowing.
+    fn relative_range(&self, absolute_range: TextRange) -> TextRange {
+        let start = absolute_range.start().max(self.global_offset);
+        let end = absolute_range.end().max(start);
+        TextRange::new(start, end) - self.global_offset
+    }
+
+    fn record_comment(&mut self, absolute_range: TextRange) {
+        self.map.insert_comment(self.relative_range(absolute_range));
+    }
+
+    fn record_item(&mut self, absolute_range: TextRange) {
+        self.map.insert_item(self.relative_range(absolute_range));
+    }
+
     fn open_delim(&mut self, open_abs_range: TextRange) -> (tt::TokenId, usize) {
-        let token_id = tt::TokenId(self.next_id);
-        self.next_id += 1;
-        let idx = self.map.insert_delim(
-            token_id,
-            open_abs_range - self.global_offset,
-            open_abs_range - self.global_offset,
-        );
+        let relative_range = self.relative_range(open_abs_range);
+        let token_id = self.ids.alloc(relative_range);
+        let idx = self.map.insert_delim(token_id, relative_range, relative_range);
         (token_id, idx)
     }
 
+    /// Builds a subtree that doesn't come from the source as is, registering its
+    /// delimiters at the given ranges so that they can still be mapped back.
+    fn delimited_subtree(
+        &mut self,
+        kind: tt::DelimiterKind,
+        open_abs_range: TextRange,
+        close_abs_range: TextRange,
+        token_trees: Vec<tt::TokenTree>,
+    ) -> tt::Subtree {
+        let (id, idx) = self.open_delim(open_abs_range);
+        self.close_delim(idx, Some(close_abs_range));
+        tt::Subtree { delimiter: Some(tt::Delimiter { id, kind }), token_trees }
+    }
+
     fn close_delim(&mut self, idx: usize, close_abs_range: Option<TextRange>) {
         match close_abs_range {
             None => {
                 self.map.remove_delim(idx);
             }
             Some(close) => {
-                self.map.update_close_delim(idx, close - self.global_offset);
+                self.map.update_close_delim(idx, self.relative_range(close));
             }
         }
     }
 }
 
+/// Wraps `tt` in a `kind` delimiter, the inverse of collapsing a lone delimited
+/// subtree. The delimiters are mapped to the first and last character of `range`,
+/// and a `tt` that already has a delimiter is nested as a whole.
+fn wrap_in_delimiter<A: TokenIdAllocator>(
+    tt: tt::Subtree,
+    kind: tt::DelimiterKind,
+    id_alloc: &mut TokenIdAlloc<A>,
+    range: TextRange,
+) -> tt::Subtree {
+    let token_trees = match tt.delimiter {
+        None => tt.token_trees,
+        Some(_) => vec![tt.into()],
+    };
+    let char_len = range.len().min(TextSize::of('/'));
+    let open = TextRange::at(range.start(), char_len);
+    let close = TextRange::at(range.end() - char_len, char_len);
+    id_alloc.delimited_subtree(kind, open, close, token_trees)
+}
+
 /// A raw token (straight from lexer) convertor
 struct RawConvertor<'a> {
     lexed: parser::LexedStr<'a>,
     pos: usize,
-    id_alloc: TokenIdAlloc,
+    id_alloc: TokenIdAlloc<SequentialIds>,
 }
 
 trait SrcToken<Ctx>: std::fmt::Debug {
@@ -441,11 +1091,15 @@ trait SrcToken<Ctx>: std::fmt::Debug {
 
     fn to_text(&self, ctx: &Ctx) -> SmolStr;
 
+    /// Calls `f` with the text of the token, without copying it.
+    fn with_text<R>(&self, ctx: &Ctx, f: impl FnOnce(&str) -> R) -> R;
+
     fn synthetic_id(&self, ctx: &Ctx) -> Option<SyntheticTokenId>;
 }
 
 trait TokenConvertor: Sized {
     type Token: SrcToken<Self>;
+    type Ids: TokenIdAllocator;
 
     fn convert_doc_comment(&self, token: &Self::Token) -> Option<Vec<tt::TokenTree>>;
 
@@ -453,7 +1107,7 @@ trait TokenConvertor: Sized {
 
     fn peek(&self) -> Option<Self::Token>;
 
-    fn id_alloc(&mut self) -> &mut TokenIdAlloc;
+    fn id_alloc(&mut self) -> &mut TokenIdAlloc<Self::Ids>;
 }
 
 impl<'a> SrcToken<RawConvertor<'a>> for usize {
@@ -469,6 +1123,10 @@ impl<'a> SrcToken<RawConvertor<'a>> for usize {
         ctx.lexed.text(*self).into()
     }
 
+    fn with_text<R>(&self, ctx: &RawConvertor<'_>, f: impl FnOnce(&str) -> R) -> R {
+        f(ctx.lexed.text(*self))
+    }
+
     fn synthetic_id(&self, _ctx: &RawConvertor<'a>) -> Option<SyntheticTokenId> {
         None
     }
@@ -476,8 +1134,14 @@ impl<'a> SrcToken<RawConvertor<'a>> for usize {
 
 impl<'a> TokenConvertor for RawConvertor<'a> {
     type Token = usize;
+    type Ids = SequentialIds;
 
     fn convert_doc_comment(&self, &token: &usize) -> Option<Vec<tt::TokenTree>> {
+        // Unterminated comments, which only get here when lexing leniently, can't
+        // be reparsed into a token
+        if self.lexed.error(token).is_some() {
+            return None;
+        }
         let text = self.lexed.text(token);
         convert_doc_comment(&doc_comment(text))
     }
@@ -501,13 +1165,13 @@ impl<'a> TokenConvertor for RawConvertor<'a> {
         Some(self.pos)
     }
 
-    fn id_alloc(&mut self) -> &mut TokenIdAlloc {
+    fn id_alloc(&mut self) -> &mut TokenIdAlloc<Self::Ids> {
         &mut self.id_alloc
     }
 }
 
-struct Convertor {
-    id_alloc: TokenIdAlloc,
+struct Convertor<A> {
+    id_alloc: TokenIdAlloc<A>,
     current: Option<SyntaxToken>,
     current_synthetic: Vec<SyntheticToken>,
     preorder: PreorderWithTokens,
@@ -517,20 +1181,28 @@ struct Convertor {
     punct_offset: Option<(SyntaxToken, TextSize)>,
 }
 
-impl Convertor {
+impl<A> Convertor<A> {
+    /// Converts the tokens of `node` within `range`, which must lie inside of the node.
     fn new(
         node: &SyntaxNode,
+        range: TextRange,
         global_offset: TextSize,
         existing_token_map: TokenMap,
-        next_id: u32,
+        ids: A,
         mut replace: FxHashMap<SyntaxNode, Vec<SyntheticToken>>,
         mut append: FxHashMap<SyntaxNode, Vec<SyntheticToken>>,
-    ) -> Convertor {
-        let range = node.text_range();
+    ) -> Convertor<A> {
         let mut preorder = node.preorder_with_tokens();
-        let (first, synthetic) = Self::next_token(&mut preorder, &mut replace, &mut append);
+        let (first, synthetic) = loop {
+            let (token, synthetic) = Self::next_token(&mut preorder, &mut replace, &mut append);
+            // skip the tokens before the range, those after it end the conversion
+            match &token {
+                Some(it) if it.text_range().start() < range.start() => (),
+                _ => break (token, synthetic),
+            }
+        };
         Convertor {
-            id_alloc: { TokenIdAlloc { map: existing_token_map, global_offset, next_id } },
+            id_alloc: { TokenIdAlloc { map: existing_token_map, global_offset, ids } },
             current: first,
             current_synthetic: synthetic,
             preorder,
@@ -594,15 +1266,15 @@ impl SynToken {
     }
 }
 
-impl SrcToken<Convertor> for SynToken {
-    fn kind(&self, _ctx: &Convertor) -> SyntaxKind {
+impl<A> SrcToken<Convertor<A>> for SynToken {
+    fn kind(&self, _ctx: &Convertor<A>) -> SyntaxKind {
         match self {
             SynToken::Ordinary(token) => token.kind(),
             SynToken::Punch(token, _) => token.kind(),
             SynToken::Synthetic(token) => token.kind,
         }
     }
-    fn to_char(&self, _ctx: &Convertor) -> Option<char> {
+    fn to_char(&self, _ctx: &Convertor<A>) -> Option<char> {
         match self {
             SynToken::Ordinary(_) => None,
             SynToken::Punch(it, i) => it.text().chars().nth((*i).into()),
@@ -610,15 +1282,21 @@ impl SrcToken<Convertor> for SynToken {
             SynToken::Synthetic(_) => None,
         }
     }
-    fn to_text(&self, _ctx: &Convertor) -> SmolStr {
+    fn to_text(&self, _ctx: &Convertor<A>) -> SmolStr {
         match self {
             SynToken::Ordinary(token) => token.text().into(),
             SynToken::Punch(token, _) => token.text().into(),
             SynToken::Synthetic(token) => token.text.clone(),
         }
     }
+    fn with_text<R>(&self, _ctx: &Convertor<A>, f: impl FnOnce(&str) -> R) -> R {
+        match self {
+            SynToken::Ordinary(token) | SynToken::Punch(token, _) => f(token.text()),
+            SynToken::Synthetic(token) => f(&token.text),
+        }
+    }
 
-    fn synthetic_id(&self, _ctx: &Convertor) -> Option<SyntheticTokenId> {
+    fn synthetic_id(&self, _ctx: &Convertor<A>) -> Option<SyntheticTokenId> {
         match self {
             SynToken::Synthetic(token) => Some(token.id),
             _ => None,
@@ -626,8 +1304,10 @@ impl SrcToken<Convertor> for SynToken {
     }
 }
 
-impl TokenConvertor for Convertor {
+impl<A: TokenIdAllocator> TokenConvertor for Convertor<A> {
     type Token = SynToken;
+    type Ids = A;
+
     fn convert_doc_comment(&self, token: &Self::Token) -> Option<Vec<tt::TokenTree>> {
         convert_doc_comment(token.token()?)
     }
@@ -701,7 +1381,7 @@ impl TokenConvertor for Convertor {
         Some(token)
     }
 
-    fn id_alloc(&mut self) -> &mut TokenIdAlloc {
+    fn id_alloc(&mut self) -> &mut TokenIdAlloc<Self::Ids> {
         &mut self.id_alloc
     }
 }
@@ -713,10 +1393,17 @@ struct TtTreeSink<'a> {
     text_pos: TextSize,
     inner: SyntaxTreeBuilder,
     token_map: TokenMap,
+    config: ReconstructConfig,
+    /// The map of the source, to restore the whitespace inside delimiters from.
+    source_map: Option<&'a TokenMap>,
 }
 
 impl<'a> TtTreeSink<'a> {
-    fn new(cursor: Cursor<'a>) -> Self {
+    fn new(
+        cursor: Cursor<'a>,
+        config: ReconstructConfig,
+        source_map: Option<&'a TokenMap>,
+    ) -> Self {
         TtTreeSink {
             buf: String::new(),
             cursor,
@@ -724,6 +1411,8 @@ impl<'a> TtTreeSink<'a> {
             text_pos: 0.into(),
             inner: SyntaxTreeBuilder::default(),
             token_map: TokenMap::default(),
+            config,
+            source_map,
         }
     }
 
@@ -731,6 +1420,23 @@ impl<'a> TtTreeSink<'a> {
         self.token_map.shrink_to_fit();
         (self.inner.finish(), self.token_map)
     }
+
+    /// Where to put a space inside `subtree`'s delimiters. Empty subtrees only
+    /// get one after the opening delimiter.
+    fn delimiter_spacing(&self, subtree: &tt::Subtree) -> DelimiterSpacing {
+        match (self.source_map, subtree.delimiter) {
+            (Some(map), Some(d)) if !subtree.token_trees.is_empty() => map.delimiter_spacing(d.id),
+            (Some(map), Some(d)) => {
+                DelimiterSpacing { before_close: false, ..map.delimiter_spacing(d.id) }
+            }
+            _ => DelimiterSpacing::default(),
+        }
+    }
+
+    fn whitespace(&mut self) {
+        self.inner.token(WHITESPACE, " ");
+        self.text_pos += TextSize::of(' ');
+    }
 }
 
 fn delim_to_str(d: tt::DelimiterKind, closing: bool) -> &'static str {
@@ -744,6 +1450,31 @@ fn delim_to_str(d: tt::DelimiterKind, closing: bool) -> &'static str {
     &texts[idx..texts.len() - (1 - idx)]
 }
 
+/// Whether whitespace has to go between two adjacent token trees for them to be
+/// lexed back into the same tokens, going by the token trees alone.
+fn needs_space_between(left: &TokenTreeRef, right: &TokenTreeRef) -> bool {
+    fn is_word_like(leaf: &tt::Leaf) -> bool {
+        matches!(leaf, tt::Leaf::Ident(_) | tt::Leaf::Literal(_))
+    }
+
+    match (left, right) {
+        // Add whitespace between adjoint puncts
+        (
+            TokenTreeRef::Leaf(tt::Leaf::Punct(left), _),
+            TokenTreeRef::Leaf(tt::Leaf::Punct(_), _),
+        ) => {
+            // Note: We always assume the semi-colon would be the last token in
+            // other parts of RA such that we don't add whitespace here.
+            left.spacing == tt::Spacing::Alone && left.char != ';'
+        }
+        // Keep idents, keywords and literals from being glued into a single token
+        (TokenTreeRef::Leaf(left, _), TokenTreeRef::Leaf(right, _)) => {
+            is_word_like(left) && is_word_like(right)
+        }
+        _ => false,
+    }
+}
+
 impl<'a> TtTreeSink<'a> {
     fn token(&mut self, kind: SyntaxKind, mut n_tokens: u8) {
         if kind == LIFETIME_IDENT {
@@ -751,6 +1482,7 @@ impl<'a> TtTreeSink<'a> {
         }
 
         let mut last = self.cursor;
+        let mut space_after_open = false;
         for _ in 0..n_tokens {
             let tmp: u8;
             if self.cursor.eof() {
@@ -779,6 +1511,7 @@ impl<'a> TtTreeSink<'a> {
                         self.cursor = self.cursor.subtree().unwrap();
                         match subtree.delimiter {
                             Some(d) => {
+                                space_after_open = self.delimiter_spacing(subtree).after_open;
                                 self.open_delims.insert(d.id, self.text_pos);
                                 delim_to_str(d.kind, false)
                             }
@@ -790,6 +1523,10 @@ impl<'a> TtTreeSink<'a> {
                         self.cursor = self.cursor.bump();
                         match parent.delimiter {
                             Some(d) => {
+                                // a closing delimiter is always a token of its own
+                                if self.delimiter_spacing(parent).before_close {
+                                    self.whitespace();
+                                }
                                 if let Some(open_delim) = self.open_delims.get(&d.id) {
                                     let open_range = TextRange::at(*open_delim, TextSize::of('('));
                                     let close_range =
@@ -809,19 +1546,21 @@ impl<'a> TtTreeSink<'a> {
 
         self.inner.token(kind, self.buf.as_str());
         self.buf.clear();
-        // Add whitespace between adjoint puncts
         let next = last.bump();
-        if let (
-            Some(tt::buffer::TokenTreeRef::Leaf(tt::Leaf::Punct(curr), _)),
-            Some(tt::buffer::TokenTreeRef::Leaf(tt::Leaf::Punct(_), _)),
-        ) = (last.token_tree(), next.token_tree())
-        {
-            // Note: We always assume the semi-colon would be the last token in
-            // other parts of RA such that we don't add whitespace here.
-            if curr.spacing == tt::Spacing::Alone && curr.char != ';' {
-                self.inner.token(WHITESPACE, " ");
-                self.text_pos += TextSize::of(' ');
+        let needs_space = match (last.token_tree(), next.token_tree()) {
+            (Some(curr), Some(next)) => {
+                // The parser only emits a lone `>` followed by another one when
+                // it closes generic arguments, `>>` is a shift otherwise
+                let closes_nested_generics = kind == T![>]
+                    && matches!(next, TokenTreeRef::Leaf(tt::Leaf::Punct(it), _) if it.char == '>');
+                needs_space_between(&curr, &next)
+                    || (self.config.split_closing_angles && closes_nested_generics)
+                    || (self.config.space_after_semicolon && kind == T![;])
             }
+            _ => false,
+        };
+        if needs_space || space_after_open {
+            self.whitespace();
         }
     }
 
@@ -837,3 +1576,6 @@ impl<'a> TtTreeSink<'a> {
         self.inner.error(error, self.text_pos)
     }
 }
+
+#[cfg(test)]
+mod tests;
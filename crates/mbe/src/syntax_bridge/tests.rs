@@ -0,0 +1,1009 @@
+//! Tests for the conversions between `SyntaxNode` and `TokenTree`.
+
+use rustc_hash::FxHashMap;
+use syntax::{
+    ast::{self, CommentKind, CommentPlacement, CommentShape, HasArgList},
+    AstNode, AstToken, SmolStr, SyntaxKind, TextRange, TextSize, T,
+};
+
+use super::{
+    needs_space_between, parse_to_token_tree, parse_to_token_tree_edition,
+    parse_to_token_tree_lenient, syntax_node_to_token_tree, syntax_node_to_token_tree_in_range,
+    syntax_node_to_token_tree_with_config, syntax_node_to_token_tree_with_id_allocator,
+    syntax_node_to_token_tree_with_interner, syntax_node_to_token_tree_with_leaf_kinds,
+    syntax_node_to_token_tree_with_modifications, syntax_node_to_token_tree_with_next_id,
+    syntax_node_to_token_tree_with_punct_groups, syntax_node_to_token_trees_with_spans,
+    token_tree_to_syntax_node, token_tree_to_syntax_node_checked,
+    token_tree_to_syntax_node_with_config, token_tree_to_syntax_node_with_delimiter_spacing,
+    wrap_in_delimiter, ConvertConfig, LiteralInterner, ReconstructConfig, SequentialIds,
+    SyntheticToken, SyntheticTokenId, TokenIdAlloc, TokenIdAllocator,
+};
+use tt::buffer::TokenTreeRef;
+
+use crate::{canonicalize_spacing, subtree_source_range, tt_structural_eq, TokenMapDiff};
+
+fn source_file(text: &str) -> ast::SourceFile {
+    ast::SourceFile::parse(text).ok().unwrap()
+}
+
+#[test]
+fn comment_ranges_are_recorded() {
+    let text = "// plain\n/// doc\nfn f() { /* block */ }";
+    let file = source_file(text);
+
+    let (_, map) = syntax_node_to_token_tree(file.syntax());
+    assert!(map.comment_ranges().is_empty());
+
+    let config = ConvertConfig { record_comment_ranges: true, ..ConvertConfig::default() };
+    let (subtree, map) = syntax_node_to_token_tree_with_config(file.syntax(), config);
+    let comments: Vec<_> = map.comment_ranges().iter().map(|&range| &text[range]).collect();
+    assert_eq!(comments, ["// plain", "/// doc", "/* block */"]);
+    // recording the ranges doesn't change the emitted tree
+    assert_eq!(subtree, syntax_node_to_token_tree(file.syntax()).0);
+}
+
+#[test]
+fn absolute_ranges_are_recorded() {
+    let text = "fn f() { a + b }";
+    let file = source_file(text);
+    let block = file.syntax().descendants().find_map(ast::BlockExpr::cast).unwrap();
+    let ident_text = |map: &crate::TokenMap, subtree: &tt::Subtree, offset: TextSize| {
+        let ident = match &subtree.token_trees[0] {
+            tt::TokenTree::Leaf(tt::Leaf::Ident(it)) => it,
+            _ => panic!("expected an ident"),
+        };
+        let range = map.first_range_by_token(ident.id, SyntaxKind::IDENT).unwrap();
+        text[range + offset].to_string()
+    };
+
+    let (subtree, map) = syntax_node_to_token_tree(block.syntax());
+    assert_eq!(ident_text(&map, &subtree, block.syntax().text_range().start()), "a");
+
+    let config = ConvertConfig { absolute_ranges: true, ..ConvertConfig::default() };
+    let (subtree, map) = syntax_node_to_token_tree_with_config(block.syntax(), config);
+    assert_eq!(ident_text(&map, &subtree, TextSize::default()), "a");
+}
+
+#[test]
+fn long_literals_are_shared() {
+    let long = format!("{:?}", "x".repeat(100));
+    let file = source_file(&format!("const A: &str = {}; const B: &str = \"short\";", long));
+    let literals = |tt: &tt::Subtree| -> Vec<SmolStr> {
+        tt.token_trees
+            .iter()
+            .filter_map(|it| match it {
+                tt::TokenTree::Leaf(tt::Leaf::Literal(lit)) => Some(lit.text.clone()),
+                _ => None,
+            })
+            .collect()
+    };
+
+    let mut interner = LiteralInterner::new(64);
+    let (first, _) =
+        syntax_node_to_token_tree_with_interner(file.syntax(), Default::default(), &mut interner);
+    let (second, _) =
+        syntax_node_to_token_tree_with_interner(file.syntax(), Default::default(), &mut interner);
+    assert_eq!(first, syntax_node_to_token_tree(file.syntax()).0);
+    let (first, second) = (literals(&first), literals(&second));
+    assert_eq!(first, [long.as_str(), "\"short\""]);
+    assert_eq!(first[0].as_ptr(), second[0].as_ptr());
+
+    let unshared = literals(&syntax_node_to_token_tree(file.syntax()).0);
+    assert_ne!(first[0].as_ptr(), unshared[0].as_ptr());
+}
+
+#[test]
+fn item_ranges_are_recorded() {
+    let text =
+        "/// one\nfn one() {}\n\n// two\nfn two(x: u8) -> u8 { x }\n#[inline]\nfn three() {}\n";
+    let file = source_file(text);
+    let config = ConvertConfig { record_item_ranges: true, ..ConvertConfig::default() };
+    let (subtree, map) = syntax_node_to_token_tree_with_config(file.syntax(), config);
+    let items: Vec<_> = map.item_ranges().iter().map(|&range| &text[range]).collect();
+    // leading comments belong to the item, like doc comments do
+    assert_eq!(
+        items,
+        ["/// one\nfn one() {}", "// two\nfn two(x: u8) -> u8 { x }", "#[inline]\nfn three() {}"]
+    );
+    assert_eq!(subtree, syntax_node_to_token_tree(file.syntax()).0);
+
+    // a lone delimited group is kept as is
+    let file = ast::SourceFile::parse("{ a }").tree();
+    let (subtree, _) = syntax_node_to_token_tree_with_config(file.syntax(), config);
+    assert_eq!(subtree.delimiter, None);
+    assert_eq!(subtree.to_string(), "{a}");
+    let (subtree, _) = syntax_node_to_token_tree(file.syntax());
+    assert_eq!(subtree.delimiter_kind(), Some(tt::DelimiterKind::Brace));
+}
+
+#[test]
+fn checked_conversion_round_trips() {
+    let (tt, _) = parse_to_token_tree("a + b * (c - 1)").unwrap();
+    let (parse, _) = token_tree_to_syntax_node_checked(&tt, parser::TopEntryPoint::Expr).unwrap();
+    assert_eq!(parse.syntax_node().to_string(), "a+b*(c-1)");
+
+    let (tt, _) = parse_to_token_tree("let x = 1;").unwrap();
+    let (parse, _) =
+        token_tree_to_syntax_node_checked(&tt, parser::TopEntryPoint::MacroStmts).unwrap();
+    assert_eq!(parse.syntax_node().to_string(), "let x=1;");
+}
+
+#[test]
+fn parse_errors_map_to_input() {
+    let text = "fn f() {\n    let = 1;\n}";
+    let (tt, input_map) = parse_to_token_tree(text).unwrap();
+    let (parse, expanded_map) = token_tree_to_syntax_node(&tt, parser::TopEntryPoint::MacroItems);
+    let error = &parse.errors()[0];
+    let id = expanded_map.token_by_error_offset(error.range().start()).unwrap();
+    let range = input_map.first_range_by_token(id, T![=]).unwrap();
+    assert_eq!(&text[range], "=");
+    assert_eq!(range.start(), TextSize::from(17));
+}
+
+#[test]
+fn edition_keywords_are_idents() {
+    let (tt, _) = parse_to_token_tree("async fn f(x: &dyn T) { try }").unwrap();
+    struct Idents(Vec<String>);
+    impl crate::TokenTreeVisitor for Idents {
+        fn visit_ident(&mut self, ident: &tt::Ident) {
+            self.0.push(ident.text.to_string());
+        }
+    }
+    let mut idents = Idents(Vec::new());
+    crate::walk(&tt, &mut idents);
+    assert_eq!(idents.0, ["async", "fn", "f", "x", "dyn", "T", "try"]);
+}
+
+#[test]
+fn idents_are_not_glued() {
+    let (tt, _) = parse_to_token_tree("pub fn foo() -> u8 { 1 as u8 }").unwrap();
+    let (parse, _) = token_tree_to_syntax_node_checked(&tt, parser::TopEntryPoint::MacroItems)
+        .unwrap_or_else(|err| panic!("{}", err));
+    assert_eq!(parse.syntax_node().to_string(), "pub fn foo()->u8{1 as u8}");
+}
+
+#[test]
+fn closing_angles_of_nested_generics() {
+    let (tt, _) =
+        parse_to_token_tree("type T = HashMap<K, Vec<V>>; const C: u8 = a >> b;").unwrap();
+    let reconstruct = |config| {
+        let (parse, _) =
+            token_tree_to_syntax_node_with_config(&tt, parser::TopEntryPoint::MacroItems, config);
+        assert!(parse.errors().is_empty());
+        parse.syntax_node().to_string()
+    };
+
+    assert_eq!(
+        reconstruct(ReconstructConfig::default()),
+        "type T=HashMap<K,Vec<V>>;const C:u8=a>>b;"
+    );
+    assert_eq!(
+        reconstruct(ReconstructConfig {
+            split_closing_angles: true,
+            ..ReconstructConfig::default()
+        }),
+        "type T=HashMap<K,Vec<V> >;const C:u8=a>>b;"
+    );
+}
+
+#[test]
+fn space_after_semicolons() {
+    let config = ReconstructConfig { space_after_semicolon: true, ..ReconstructConfig::default() };
+    let reconstruct = |text, entry_point| {
+        let (mut tt, _) = parse_to_token_tree(text).unwrap();
+        let (parse, _) = token_tree_to_syntax_node_with_config(&tt, entry_point, config);
+        assert!(parse.errors().is_empty());
+        let text = parse.syntax_node().to_string();
+        // the text round-trips to the same tokens, the spacing before a closing
+        // delimiter aside
+        let (mut reparsed, _) = parse_to_token_tree(&text).unwrap();
+        canonicalize_spacing(&mut tt);
+        canonicalize_spacing(&mut reparsed);
+        assert!(tt_structural_eq(&tt, &reparsed), "{}", text);
+        text
+    };
+
+    assert_eq!(
+        reconstruct("a; b; let c = 1; { d; e }", parser::TopEntryPoint::MacroStmts),
+        "a; b; let c=1; {d; e}"
+    );
+    assert_eq!(
+        reconstruct(
+            "fn f() { a; b; } struct S; const C: u8 = 1;",
+            parser::TopEntryPoint::MacroItems
+        ),
+        "fn f(){a; b;}struct S; const C:u8=1;"
+    );
+
+    let (tt, _) = parse_to_token_tree("a; b;").unwrap();
+    let (parse, _) = token_tree_to_syntax_node(&tt, parser::TopEntryPoint::MacroStmts);
+    assert_eq!(parse.syntax_node().to_string(), "a;b;");
+}
+
+#[test]
+fn delimiter_spacing_is_preserved() {
+    let text = "fn f() { vec![ 1, 2 ]; g( x, [y],\n) ; h( ); }";
+    let file = source_file(text);
+    let config = ConvertConfig { record_delimiter_spacing: true, ..ConvertConfig::default() };
+    let (tt, map) = syntax_node_to_token_tree_with_config(file.syntax(), config);
+
+    let (parse, _) = token_tree_to_syntax_node(&tt, parser::TopEntryPoint::MacroItems);
+    assert_eq!(parse.syntax_node().to_string(), "fn f(){vec![1,2];g(x,[y],);h();}");
+
+    let (parse, new_map) = token_tree_to_syntax_node_with_delimiter_spacing(
+        &tt,
+        parser::TopEntryPoint::MacroItems,
+        ReconstructConfig::default(),
+        &map,
+    );
+    assert!(parse.errors().is_empty());
+    let new_text = parse.syntax_node().to_string();
+    assert_eq!(new_text, "fn f(){ vec![ 1,2 ];g( x,[y], );h( ); }");
+
+    // the delimiters are still mapped to where they ended up
+    let id = match &tt.token_trees[3] {
+        tt::TokenTree::Subtree(it) => it.delimiter.unwrap().id,
+        it => panic!("expected the braces of `f`, got {:?}", it),
+    };
+    let (open, close) = new_map.delimiter_ranges(id).unwrap();
+    assert_eq!((&new_text[open], &new_text[close]), ("{", "}"));
+}
+
+#[test]
+fn space_between_token_trees() {
+    let punct = |char, spacing| tt::Leaf::from(tt::Punct { char, spacing, id: tt::TokenId(0) });
+    let trees: Vec<(&str, tt::TokenTree)> = vec![
+        ("ident", tt::Leaf::from(tt::Ident { text: "a".into(), id: tt::TokenId(0) }).into()),
+        ("literal", tt::Leaf::from(tt::Literal { text: "1".into(), id: tt::TokenId(0) }).into()),
+        ("alone +", punct('+', tt::Spacing::Alone).into()),
+        ("joint +", punct('+', tt::Spacing::Joint).into()),
+        ("alone ;", punct(';', tt::Spacing::Alone).into()),
+        ("joint ;", punct(';', tt::Spacing::Joint).into()),
+        ("subtree", tt::Subtree::default().into()),
+    ];
+    fn as_ref(tree: &tt::TokenTree) -> TokenTreeRef<'_> {
+        match tree {
+            tt::TokenTree::Leaf(leaf) => TokenTreeRef::Leaf(leaf, tree),
+            tt::TokenTree::Subtree(subtree) => TokenTreeRef::Subtree(subtree, Some(tree)),
+        }
+    }
+
+    let mut spaced = Vec::new();
+    for (left_name, left) in &trees {
+        for (right_name, right) in &trees {
+            if needs_space_between(&as_ref(left), &as_ref(right)) {
+                spaced.push(format!("{} {}", left_name, right_name));
+            }
+        }
+    }
+    assert_eq!(
+        spaced,
+        [
+            "ident ident",
+            "ident literal",
+            "literal ident",
+            "literal literal",
+            "alone + alone +",
+            "alone + joint +",
+            "alone + alone ;",
+            "alone + joint ;",
+        ]
+    );
+}
+
+#[test]
+fn underscore_round_trips() {
+    let (tt, _) = parse_to_token_tree("let _ = x; let _foo = match y { _ => () };").unwrap();
+    let underscores: Vec<_> = tt
+        .token_trees
+        .iter()
+        .filter_map(|tt| match tt {
+            tt::TokenTree::Leaf(tt::Leaf::Ident(it)) if it.text.starts_with('_') => {
+                Some(it.text.as_str())
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(underscores, ["_", "_foo"]);
+    // `_` is an ident, so the `=` after it isn't glued onto it
+    match &tt.token_trees[2] {
+        tt::TokenTree::Leaf(tt::Leaf::Punct(it)) => {
+            assert_eq!((it.char, it.spacing), ('=', tt::Spacing::Alone))
+        }
+        it => panic!("expected `=`, got {:?}", it),
+    }
+
+    let (parse, _) =
+        token_tree_to_syntax_node_checked(&tt, parser::TopEntryPoint::MacroStmts).unwrap();
+    assert_eq!(parse.syntax_node().to_string(), "let _=x;let _foo=match y{_=>()};");
+    assert!(parse.syntax_node().descendants().any(|it| ast::WildcardPat::can_cast(it.kind())));
+}
+
+#[test]
+fn doc_comment_kinds_are_recorded() {
+    let file = source_file("//! inner\n/// outer\n/** block */\nfn f() {}");
+    let config = ConvertConfig { record_doc_comment_kinds: true, ..ConvertConfig::default() };
+    let (subtree, map) = syntax_node_to_token_tree_with_config(file.syntax(), config);
+
+    let mut kinds = Vec::new();
+    for tt in &subtree.token_trees {
+        if let tt::TokenTree::Subtree(meta) = tt {
+            if let Some(tt::TokenTree::Leaf(tt::Leaf::Literal(lit))) = meta.token_trees.get(2) {
+                kinds.push(map.doc_comment_kind(lit.id).unwrap());
+            }
+        }
+    }
+    assert_eq!(
+        kinds,
+        [
+            CommentKind { shape: CommentShape::Line, doc: Some(CommentPlacement::Inner) },
+            CommentKind { shape: CommentShape::Line, doc: Some(CommentPlacement::Outer) },
+            CommentKind { shape: CommentShape::Block, doc: Some(CommentPlacement::Outer) },
+        ]
+    );
+}
+
+#[test]
+fn keyword_kinds_are_recorded() {
+    let file = source_file("pub fn f(self) -> bool { true }");
+    let config = ConvertConfig { record_keyword_kinds: true, ..ConvertConfig::default() };
+    let (subtree, map) = syntax_node_to_token_tree_with_config(file.syntax(), config);
+
+    fn collect_idents(tt: &tt::Subtree, acc: &mut Vec<tt::Ident>) {
+        for tree in &tt.token_trees {
+            match tree {
+                tt::TokenTree::Leaf(tt::Leaf::Ident(ident)) => acc.push(ident.clone()),
+                tt::TokenTree::Leaf(_) => (),
+                tt::TokenTree::Subtree(subtree) => collect_idents(subtree, acc),
+            }
+        }
+    }
+    let mut idents = Vec::new();
+    collect_idents(&subtree, &mut idents);
+    let idents: Vec<_> = idents.into_iter().map(|it| (it.text, map.keyword_kind(it.id))).collect();
+    assert_eq!(
+        idents,
+        [
+            ("pub".into(), Some(T![pub])),
+            ("fn".into(), Some(T![fn])),
+            ("f".into(), None),
+            ("self".into(), Some(T![self])),
+            ("bool".into(), None),
+            ("true".into(), Some(T![true])),
+        ]
+    );
+
+    let (_, map) = syntax_node_to_token_tree(file.syntax());
+    assert_eq!(map.keyword_kind(tt::TokenId(0)), None);
+}
+
+#[test]
+fn leaf_kinds_are_recorded() {
+    let file = source_file(r#"const C: X<'a> = f(b"a", "a", 1, 1.5, true);"#);
+    let (subtree, _, kinds) =
+        syntax_node_to_token_tree_with_leaf_kinds(file.syntax(), ConvertConfig::default());
+    // recording the kinds doesn't change the emitted tree
+    assert_eq!(subtree, syntax_node_to_token_tree(file.syntax()).0);
+
+    fn collect_leaves(tt: &tt::Subtree, acc: &mut Vec<tt::Leaf>) {
+        for tree in &tt.token_trees {
+            match tree {
+                tt::TokenTree::Leaf(leaf) => acc.push(leaf.clone()),
+                tt::TokenTree::Subtree(subtree) => collect_leaves(subtree, acc),
+            }
+        }
+    }
+    let mut leaves = Vec::new();
+    collect_leaves(&subtree, &mut leaves);
+    let leaves: Vec<_> = leaves.iter().map(|leaf| (leaf.to_string(), kinds[&leaf.id()])).collect();
+    assert_eq!(
+        leaves,
+        [
+            ("const", T![const]),
+            ("C", SyntaxKind::IDENT),
+            (":", T![:]),
+            ("X", SyntaxKind::IDENT),
+            ("<", T![<]),
+            ("'", SyntaxKind::LIFETIME_IDENT),
+            ("a", SyntaxKind::LIFETIME_IDENT),
+            (">", T![>]),
+            ("=", T![=]),
+            ("f", SyntaxKind::IDENT),
+            ("b\"a\"", SyntaxKind::BYTE_STRING),
+            (",", T![,]),
+            ("\"a\"", SyntaxKind::STRING),
+            (",", T![,]),
+            ("1", SyntaxKind::INT_NUMBER),
+            (",", T![,]),
+            ("1.5", SyntaxKind::FLOAT_NUMBER),
+            (",", T![,]),
+            ("true", T![true]),
+            (";", T![;]),
+        ]
+        .map(|(text, kind)| (text.to_owned(), kind))
+    );
+}
+
+#[test]
+fn punct_groups_are_recorded() {
+    let file = source_file("fn f() -> Vec<Vec<u8>> { a == b && !c; x..=y; m::n }");
+    let (subtree, _, groups) =
+        syntax_node_to_token_tree_with_punct_groups(file.syntax(), ConvertConfig::default());
+    // the puncts are split all the same
+    assert_eq!(subtree, syntax_node_to_token_tree(file.syntax()).0);
+
+    fn collect_puncts(tt: &tt::Subtree, acc: &mut Vec<tt::Punct>) {
+        for tree in &tt.token_trees {
+            match tree {
+                tt::TokenTree::Leaf(tt::Leaf::Punct(punct)) => acc.push(*punct),
+                tt::TokenTree::Leaf(_) => (),
+                tt::TokenTree::Subtree(subtree) => collect_puncts(subtree, acc),
+            }
+        }
+    }
+    let mut puncts = Vec::new();
+    collect_puncts(&subtree, &mut puncts);
+    let mut operators = Vec::new();
+    let mut iter = puncts.iter();
+    while let Some(punct) = iter.next() {
+        let mut op = punct.char.to_string();
+        if let Some(group) = groups.get(&punct.id) {
+            op.extend(iter.by_ref().take(group.len - 1).map(|it| it.char));
+            assert_eq!(group.len, op.len());
+        }
+        operators.push(op);
+    }
+    // the `>>` closing the generics are two tokens already
+    assert_eq!(operators, ["->", "<", "<", ">", ">", "==", "&&", "!", ";", "..=", ";", "::"]);
+    let kinds: Vec<_> =
+        puncts.iter().filter_map(|it| groups.get(&it.id)).map(|it| it.kind).collect();
+    assert_eq!(kinds, [T![->], T![==], T![&&], T![..=], T![::]]);
+}
+
+#[test]
+fn mixed_attrs_and_doc_comments_keep_source_order() {
+    let text = "
+/// outer
+#[attr]
+/** block */
+mod m {
+    //! inner
+    #![inner_attr]
+    //! more
+    fn f() {}
+}";
+    let file = source_file(text);
+    let (tt, _) = syntax_node_to_token_tree(file.syntax());
+    assert_eq!(
+        tt.to_string(),
+        "# [doc = \" outer\"] # [attr] # [doc = \" block \"] mod m {\
+         # ! [doc = \" inner\"] #! [inner_attr] # ! [doc = \" more\"] fn f () {}}"
+    );
+    let (parse, _) = token_tree_to_syntax_node_checked(&tt, parser::TopEntryPoint::MacroItems)
+        .unwrap_or_else(|err| panic!("{}", err));
+    let attrs: Vec<_> = parse
+        .syntax_node()
+        .descendants()
+        .filter_map(ast::Attr::cast)
+        .map(|attr| (attr.kind(), attr.simple_name().unwrap().to_string()))
+        .collect();
+    let outer = |name: &str| (ast::AttrKind::Outer, name.to_string());
+    let inner = |name: &str| (ast::AttrKind::Inner, name.to_string());
+    assert_eq!(
+        attrs,
+        [
+            outer("doc"),
+            outer("attr"),
+            outer("doc"),
+            inner("doc"),
+            inner("inner_attr"),
+            inner("doc")
+        ]
+    );
+
+    // tokens appended to an attribute stay between it and the next doc comment
+    let attrs: Vec<_> = file.syntax().descendants().filter_map(ast::Attr::cast).collect();
+    let synthetic = |text: &str, attr: &ast::Attr| SyntheticToken {
+        kind: SyntaxKind::IDENT,
+        text: text.into(),
+        range: TextRange::empty(attr.syntax().text_range().end()),
+        id: SyntheticTokenId(0),
+    };
+    let append = FxHashMap::from_iter(
+        [("x", &attrs[0]), ("y", &attrs[1])]
+            .map(|(text, attr)| (attr.syntax().clone(), vec![synthetic(text, attr)])),
+    );
+    let (tt, _, _) = syntax_node_to_token_tree_with_modifications(
+        file.syntax(),
+        Default::default(),
+        0,
+        Default::default(),
+        append,
+    );
+    assert_eq!(
+        tt.to_string(),
+        "# [doc = \" outer\"] # [attr] x # [doc = \" block \"] mod m {\
+         # ! [doc = \" inner\"] #! [inner_attr] y # ! [doc = \" more\"] fn f () {}}"
+    );
+}
+
+/// Whether `literal` is a single string literal with the value `body`.
+fn doc_literal_round_trips(body: &str, literal: &str) -> bool {
+    let file = ast::SourceFile::parse(&format!("const _: &str = {};", literal)).tree();
+    let value = file
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .find_map(ast::String::cast)
+        .filter(|it| it.text() == literal)
+        .and_then(|it| it.value().map(|it| it.into_owned()));
+    value.as_deref() == Some(body)
+}
+
+#[test]
+fn doc_comment_escaping_round_trips() {
+    let bodies = [" plain", " \"quotes\" and \\ \\n", "\ttab\nnewline", " é e\u{301} \u{202e}"];
+    for body in bodies {
+        let file = source_file(&format!("/**{}*/\nfn f() {{}}", body));
+        let (tt, _) = syntax_node_to_token_tree(file.syntax());
+        let literal = match &tt.token_trees[1] {
+            tt::TokenTree::Subtree(meta) => meta.token_trees[2].to_string(),
+            _ => panic!("expected the doc attribute"),
+        };
+        assert!(doc_literal_round_trips(body, &literal), "{:?} became {}", body, literal);
+    }
+
+    assert!(!doc_literal_round_trips("a", r#""b""#));
+    assert!(!doc_literal_round_trips(r#"a"b"#, r#""a"b""#));
+}
+
+#[test]
+fn doc_comments_with_crlf() {
+    let file = source_file("/** a\r\n b */\r\n/// c\r\nfn f() {}");
+    let (subtree, _) = syntax_node_to_token_tree(file.syntax());
+
+    let mut docs = Vec::new();
+    for tt in &subtree.token_trees {
+        if let tt::TokenTree::Subtree(meta) = tt {
+            if let Some(tt::TokenTree::Leaf(tt::Leaf::Literal(lit))) = meta.token_trees.get(2) {
+                docs.push(lit.text.clone());
+            }
+        }
+    }
+    assert_eq!(docs, [r#"" a\n b ""#, r#"" c""#]);
+}
+
+#[test]
+fn custom_id_allocator() {
+    struct RangeIds;
+    impl TokenIdAllocator for RangeIds {
+        fn alloc(&mut self, relative_range: TextRange) -> tt::TokenId {
+            tt::TokenId(relative_range.start().into())
+        }
+    }
+
+    let text = "fn f() { a::b }";
+    let file = source_file(text);
+    let (subtree, map) = syntax_node_to_token_tree_with_id_allocator(
+        file.syntax(),
+        ConvertConfig::default(),
+        RangeIds,
+    );
+
+    let mut leaves = Vec::new();
+    let mut stack = vec![&subtree];
+    while let Some(subtree) = stack.pop() {
+        for tt in &subtree.token_trees {
+            match tt {
+                tt::TokenTree::Leaf(leaf) => leaves.push(leaf),
+                tt::TokenTree::Subtree(it) => stack.push(it),
+            }
+        }
+    }
+    for leaf in leaves {
+        let id = match leaf {
+            tt::Leaf::Literal(it) => it.id,
+            tt::Leaf::Punct(it) => it.id,
+            tt::Leaf::Ident(it) => it.id,
+        };
+        let range = map.first_range_by_token(id, syntax::SyntaxKind::IDENT).unwrap();
+        assert_eq!(u32::from(range.start()), id.0);
+    }
+
+    // the default allocator hands out consecutive ids
+    let (subtree, _) = syntax_node_to_token_tree(file.syntax());
+    let ids: Vec<_> = subtree
+        .token_trees
+        .iter()
+        .filter_map(|tt| match tt {
+            tt::TokenTree::Leaf(tt::Leaf::Ident(it)) => Some(it.id.0),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(ids, [0, 1]);
+}
+
+#[test]
+fn chained_conversions_dont_reuse_ids() {
+    let file = source_file("fn a() {} fn b(x: u8) {}");
+    let mut fns = file.syntax().children();
+    let (first, _, next_id) = syntax_node_to_token_tree_with_next_id(&fns.next().unwrap(), 0);
+    assert_eq!(next_id, 4);
+    let (second, _, last_id) =
+        syntax_node_to_token_tree_with_next_id(&fns.next().unwrap(), next_id);
+    assert_eq!(last_id, 11);
+
+    let ident_ids = |subtree: &tt::Subtree| -> Vec<u32> {
+        subtree
+            .token_trees
+            .iter()
+            .filter_map(|tt| match tt {
+                tt::TokenTree::Leaf(tt::Leaf::Ident(it)) => Some(it.id.0),
+                _ => None,
+            })
+            .collect()
+    };
+    assert_eq!(ident_ids(&first), [0, 1]);
+    assert_eq!(ident_ids(&second), [4, 5]);
+}
+
+#[test]
+fn token_map_diff() {
+    let (_, old) = syntax_node_to_token_tree(source_file("fn f() { a }").syntax());
+    assert!(old.diff(&old).is_empty());
+
+    let (_, new) = syntax_node_to_token_tree(source_file("fn f() { ab; }").syntax());
+    let range = |start: u32, end: u32| TextRange::new(TextSize::from(start), TextSize::from(end));
+    assert_eq!(
+        old.diff(&new),
+        TokenMapDiff {
+            added: vec![tt::TokenId(5)],
+            removed: vec![],
+            moved: vec![
+                (tt::TokenId(3), range(7, 12), range(7, 14)),
+                (tt::TokenId(4), range(9, 10), range(9, 11)),
+            ],
+        }
+    );
+    assert_eq!(new.diff(&old).removed, [tt::TokenId(5)]);
+}
+
+#[test]
+fn rebased_token_map() {
+    let file = source_file("fn f() {\n    // c\n    fn g() {}\n}");
+    let body = file.syntax().descendants().find_map(ast::BlockExpr::cast).unwrap();
+    let config = ConvertConfig {
+        record_comment_ranges: true,
+        record_item_ranges: true,
+        ..ConvertConfig::default()
+    };
+    let (_, relative) = syntax_node_to_token_tree_with_config(body.syntax(), config);
+    let (_, absolute) = syntax_node_to_token_tree_with_config(
+        body.syntax(),
+        ConvertConfig { absolute_ranges: true, ..config },
+    );
+    let offset = body.syntax().text_range().start();
+
+    let mut map = relative.clone();
+    map.rebase(offset);
+    assert_eq!(map, absolute);
+    assert!(map.checked_rebase_back(offset));
+    assert_eq!(map, relative);
+
+    // the first range starts at 0, going back any further fails without changes
+    assert!(!map.checked_rebase_back(1.into()));
+    assert_eq!(map, relative);
+}
+
+#[test]
+fn delimiter_ranges() {
+    let text = "fn f() { a }";
+    let (subtree, map) = syntax_node_to_token_tree(source_file(text).syntax());
+    let (block, a) = match &subtree.token_trees[3] {
+        tt::TokenTree::Subtree(it) => match &it.token_trees[0] {
+            tt::TokenTree::Leaf(tt::Leaf::Ident(a)) => (it.delimiter.unwrap(), a),
+            _ => panic!("expected an ident"),
+        },
+        _ => panic!("expected the fn body"),
+    };
+    assert!(map.is_delimiter(block.id));
+    let (open, close) = map.delimiter_ranges(block.id).unwrap();
+    assert_eq!((&text[open], &text[close]), ("{", "}"));
+
+    assert!(!map.is_delimiter(a.id));
+    assert_eq!(map.delimiter_ranges(a.id), None);
+}
+
+#[test]
+fn wrapping_in_a_delimiter() {
+    let text = "g(a, b)";
+    let (args, _) = parse_to_token_tree("a, b").unwrap();
+    let mut id_alloc = TokenIdAlloc {
+        map: Default::default(),
+        global_offset: 0.into(),
+        ids: SequentialIds { next_id: 3 },
+    };
+    // the arguments of `g` in `text`
+    let range = TextRange::new(1.into(), 7.into());
+
+    let wrapped =
+        wrap_in_delimiter(args.clone(), tt::DelimiterKind::Parenthesis, &mut id_alloc, range);
+    let delim = wrapped.delimiter.unwrap();
+    assert_eq!(delim.kind, tt::DelimiterKind::Parenthesis);
+    assert_eq!(delim.id, tt::TokenId(3));
+    assert_eq!(wrapped.token_trees, args.token_trees);
+    let (open, close) = id_alloc.map.delimiter_ranges(delim.id).unwrap();
+    assert_eq!((&text[open], &text[close]), ("(", ")"));
+
+    // a delimited subtree is nested instead of losing its delimiter
+    let nested = wrap_in_delimiter(wrapped.clone(), tt::DelimiterKind::Brace, &mut id_alloc, range);
+    assert_eq!(nested.token_trees, vec![tt::TokenTree::Subtree(wrapped)]);
+}
+
+#[test]
+fn subtree_source_ranges() {
+    let text = "fn f() { a + b }";
+    let file = source_file(text);
+    let (subtree, map) = syntax_node_to_token_tree(file.syntax());
+    let range = |tt: &tt::Subtree| subtree_source_range(tt, &map).map(|it| &text[it]);
+    assert_eq!(range(&subtree), Some(text));
+    let block = match &subtree.token_trees[3] {
+        tt::TokenTree::Subtree(it) => it,
+        _ => panic!("expected the fn body"),
+    };
+    assert_eq!(range(block), Some("{ a + b }"));
+    let undelimited = tt::Subtree { delimiter: None, token_trees: block.token_trees[1..].to_vec() };
+    assert_eq!(range(&undelimited), Some("+ b"));
+
+    let tail = file.syntax().descendants().find_map(ast::BlockExpr::cast).unwrap().tail_expr();
+    let synthetic = SyntheticToken {
+        kind: SyntaxKind::IDENT,
+        text: "c".into(),
+        range: TextRange::empty(TextSize::from(9)),
+        id: SyntheticTokenId(0),
+    };
+    let replace = FxHashMap::from_iter([(tail.unwrap().syntax().clone(), vec![synthetic])]);
+    let (subtree, map, _) = syntax_node_to_token_tree_with_modifications(
+        file.syntax(),
+        Default::default(),
+        0,
+        replace,
+        Default::default(),
+    );
+    let block = match &subtree.token_trees[3] {
+        tt::TokenTree::Subtree(it) => it,
+        _ => panic!("expected the fn body"),
+    };
+    assert_eq!(block.to_string(), "{c}");
+    let only_synthetic = tt::Subtree { delimiter: None, token_trees: block.token_trees.clone() };
+    assert_eq!(subtree_source_range(&only_synthetic, &map), None);
+}
+
+#[test]
+fn synthetic_tokens_are_counted() {
+    let file = source_file("fn f() { a + b }");
+    let tail = file.syntax().descendants().find_map(ast::BlockExpr::cast).unwrap().tail_expr();
+    let synthetic = |kind, text: &str, id| SyntheticToken {
+        kind,
+        text: text.into(),
+        range: TextRange::at(TextSize::from(9), TextSize::of(text)),
+        id: SyntheticTokenId(id),
+    };
+    let replace = FxHashMap::from_iter([(
+        tail.unwrap().syntax().clone(),
+        vec![synthetic(SyntaxKind::LIFETIME_IDENT, "'x", 0), synthetic(SyntaxKind::IDENT, "c", 1)],
+    )]);
+    let (_, map, _) = syntax_node_to_token_tree_with_modifications(
+        file.syntax(),
+        Default::default(),
+        0,
+        replace,
+        Default::default(),
+    );
+    assert_eq!(map.synthetic_token_count(), 2);
+    assert_eq!(map.synthetic_entries.len(), 3);
+
+    let (_, map) = syntax_node_to_token_tree(file.syntax());
+    assert_eq!(map.synthetic_token_count(), 0);
+}
+
+#[test]
+fn tokens_by_range() {
+    let text = "fn f() { a + bc }";
+    let file = source_file(text);
+    let block = file.syntax().descendants().find_map(ast::BlockExpr::cast).unwrap();
+    let offset = block.syntax().text_range().start();
+    let texts = |map: &crate::TokenMap, range: TextRange, offset: TextSize| {
+        map.ranges_by_range(range, offset)
+            .into_iter()
+            .map(|(_, range)| &text[range])
+            .collect::<Vec<_>>()
+    };
+    let range = TextRange::new(7.into(), 14.into());
+    assert_eq!(&text[range], "{ a + b");
+
+    let (_, map) = syntax_node_to_token_tree(block.syntax());
+    assert_eq!(texts(&map, range, offset), ["{", "a", "+", "bc"]);
+    assert_eq!(texts(&map, TextRange::new(9.into(), 17.into()), offset), ["a", "+", "bc", "}"]);
+    assert_eq!(texts(&map, TextRange::empty(14.into()), offset), ["bc"]);
+    let (open, close) = map.delimiter_ranges(map.first_by_range(range, offset).unwrap()).unwrap();
+    assert_eq!(
+        (open + offset, close + offset),
+        (TextRange::at(7.into(), 1.into()), TextRange::at(16.into(), 1.into()))
+    );
+
+    let config = ConvertConfig { absolute_ranges: true, ..ConvertConfig::default() };
+    let (_, map) = syntax_node_to_token_tree_with_config(block.syntax(), config);
+    assert_eq!(texts(&map, range, TextSize::default()), ["{", "a", "+", "bc"]);
+}
+
+#[test]
+fn doc_comment_brackets_are_mapped() {
+    let text = "/// doc\nfn f() {}";
+    let (subtree, map) = syntax_node_to_token_tree(source_file(text).syntax());
+    let delim = match &subtree.token_trees[1] {
+        tt::TokenTree::Subtree(it) => it.delimiter.unwrap(),
+        _ => panic!("expected the doc attribute's brackets"),
+    };
+    assert_ne!(delim.id, tt::TokenId::unspecified());
+    let open = map.first_range_by_token(delim.id, T!['[']).unwrap();
+    let close = map.first_range_by_token(delim.id, T![']']).unwrap();
+    assert_eq!((&text[open], &text[close]), ("/", "c"));
+}
+
+#[test]
+fn out_of_range_synthetic_token_is_clamped() {
+    let file = source_file("fn f() { a }");
+    let block = file.syntax().descendants().find_map(ast::BlockExpr::cast).unwrap();
+    let tail = block.tail_expr().unwrap();
+    let bogus = SyntheticToken {
+        kind: SyntaxKind::IDENT,
+        text: "b".into(),
+        range: TextRange::empty(TextSize::from(0)),
+        id: SyntheticTokenId(0),
+    };
+    let append = FxHashMap::from_iter([(tail.syntax().clone(), vec![bogus])]);
+
+    let (subtree, map, _) = syntax_node_to_token_tree_with_modifications(
+        block.syntax(),
+        Default::default(),
+        0,
+        Default::default(),
+        append,
+    );
+    assert_eq!(subtree.to_string(), "{a b}");
+    let id = map.synthetic_entries[0].0;
+    assert_eq!(map.first_range_by_token(id, SyntaxKind::IDENT), Some(TextRange::empty(0.into())));
+}
+
+#[test]
+fn lenient_parse_of_unterminated_tokens() {
+    assert!(parse_to_token_tree(r#"foo!("hello"#).is_none());
+
+    let (tt, _) = parse_to_token_tree_lenient(r#"foo!("hello"#).unwrap();
+    // the unclosed parenthesis is flattened into a punct, like elsewhere
+    assert_eq!(tt.to_string(), r#"foo ! ( "hello"#);
+    let (tt, _) = parse_to_token_tree_lenient("a /* b").unwrap();
+    assert_eq!(tt.to_string(), "a");
+
+    // other lexer errors are still rejected
+    assert!(parse_to_token_tree_lenient("0b").is_none());
+}
+
+#[test]
+fn token_trees_with_spans() {
+    let text = "struct S;\n/// docs\nfn f(a: u8) -> u8 { a }";
+    let file = source_file(text);
+    let func = file.syntax().descendants().find_map(ast::Fn::cast).unwrap();
+    let spans: Vec<_> = syntax_node_to_token_trees_with_spans(func.syntax(), Some(3))
+        .into_iter()
+        .map(|(_, span)| {
+            assert_eq!(span.file_id, Some(3));
+            &text[span.range]
+        })
+        .collect();
+    assert_eq!(spans, ["/// docs", "/// docs", "fn", "f", "(a: u8)", "-", ">", "u8", "{ a }"]);
+
+    let block = file.syntax().descendants().find_map(ast::BlockExpr::cast).unwrap();
+    let trees = syntax_node_to_token_trees_with_spans(block.syntax(), None);
+    assert_eq!(trees.len(), 1);
+    assert_eq!(&text[trees[0].1.range], "{ a }");
+}
+
+#[test]
+fn sub_range_of_a_node() {
+    let text = "fn f() { g(a, b + (c), d) }";
+    let file = source_file(text);
+    let call = file.syntax().descendants().find_map(ast::CallExpr::cast).unwrap();
+    let arg = call.arg_list().unwrap().args().nth(1).unwrap();
+    let range = arg.syntax().text_range();
+
+    let (tt, map) = syntax_node_to_token_tree_in_range(call.syntax(), range);
+    assert_eq!(tt.to_string(), "b + (c)");
+    let ranges: Vec<_> = tt
+        .token_trees
+        .iter()
+        .map(|tree| match tree {
+            tt::TokenTree::Leaf(leaf) => map.first_range_by_token(leaf.id(), SyntaxKind::IDENT),
+            tt::TokenTree::Subtree(subtree) => subtree_source_range(subtree, &map),
+        })
+        .map(|it| &text[it.unwrap() + range.start()])
+        .collect();
+    assert_eq!(ranges, ["b", "+", "(c)"]);
+
+    // tokens only partially inside of the range are left out
+    let range = TextRange::new(range.start() + TextSize::of("b +"), range.end());
+    let (tt, _) = syntax_node_to_token_tree_in_range(call.syntax(), range);
+    assert_eq!(tt.to_string(), "(c)");
+}
+
+#[test]
+fn raw_lifetimes_round_trip() {
+    let text = "fn f<'r#foo>(x: &'r#foo u8) {}";
+    let file = source_file(text);
+    let (tt, map) = syntax_node_to_token_tree(file.syntax());
+    let lifetime: Vec<_> = tt.token_trees[3..5]
+        .iter()
+        .map(|tree| match tree {
+            tt::TokenTree::Leaf(leaf) => {
+                let range = map.first_range_by_token(leaf.id(), SyntaxKind::IDENT).unwrap();
+                (leaf.to_string(), &text[range])
+            }
+            tt::TokenTree::Subtree(_) => panic!("expected the lifetime"),
+        })
+        .collect();
+    assert_eq!(lifetime, [("'".to_owned(), "'"), ("r#foo".to_owned(), "r#foo")]);
+
+    let (parse, _) = token_tree_to_syntax_node(&tt, parser::TopEntryPoint::SourceFile);
+    assert!(parse.errors().is_empty(), "{:?}", parse.errors());
+    assert_eq!(parse.syntax_node().to_string(), "fn f< 'r#foo>(x: & 'r#foo u8){}");
+    let lifetimes: Vec<_> = parse
+        .syntax_node()
+        .descendants()
+        .filter_map(ast::Lifetime::cast)
+        .map(|it| it.text().to_string())
+        .collect();
+    assert_eq!(lifetimes, ["'r#foo", "'r#foo"]);
+}
+
+#[test]
+fn raw_lifetimes_depend_on_edition() {
+    let leaves = |edition| {
+        let (tt, _) = parse_to_token_tree_edition("'r#foo", edition).unwrap();
+        tt.token_trees.iter().map(|tree| tree.to_string()).collect::<Vec<_>>()
+    };
+    assert_eq!(leaves(parser::Edition::Edition2018), ["'", "r", "#", "foo"]);
+    assert_eq!(leaves(parser::Edition::Edition2021), ["'", "r#foo"]);
+    assert_eq!(leaves(parser::Edition::LATEST), leaves(parser::Edition::Edition2021));
+}
+
+#[test]
+fn raw_and_syntax_conversions_agree() {
+    // `parse_to_token_tree` converts the lexed tokens, `syntax_node_to_token_tree` the parsed
+    // tree; both must produce the same token tree and map for the same text
+    let corpus = [
+        "",
+        "// only a comment",
+        "{ a }",
+        "#!/usr/bin/env rust\nfn main() {}",
+        "fn f<'a>(x: &'a u8) -> u8 { x.0.1 + 1.0 + 1e3 + 1.5f32 }",
+        "fn f() { x?.0.1?; t.0.0 = 1; 1.e3; 1f32.0; 1.0.0; 0x1f; 1_000u64 }",
+        "fn f<'r#foo>(x: &'r#foo u8) { 'label: loop { break 'label; } }",
+        "fn f() { a::<b<c>>(); b'a'; \"s\"; r#\"raw\"#; 'c'; r#fn }",
+        "fn f() { a += 1; b >>= 2; c && d || !e; x..=y; ..; ::std::mem::drop(z) }",
+        "fn f() { a +/* c */+ b; a +// c\n= b; 'a/**/: loop {} }",
+        "//! inner\n/// outer\n/** block */\n#[cfg(test)]\nmod m { #![inner] }",
+        "/// a \"quoted\" \\ doc\n///\n/**/\n/*! inner block */\nfn f() {}",
+        "/** a\r\n b */\r\n/// c\r\nfn f() {}",
+        "macro_rules! m { ($($t:tt)*) => { $($t)* }; }",
+        "fn f() { a } }",
+        "fn f() { ( a ] }",
+        "fn f() { /* unterminated",
+    ];
+    for text in corpus {
+        let (raw, raw_map) = parse_to_token_tree_lenient(text).unwrap();
+        let (tt, map) = syntax_node_to_token_tree(ast::SourceFile::parse(text).tree().syntax());
+        assert_eq!(format!("{:?}", raw), format!("{:?}", tt), "{}", text);
+        assert_eq!(raw_map, map, "{}", text);
+    }
+}
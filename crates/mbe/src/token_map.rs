@@ -3,10 +3,14 @@
 use std::hash::Hash;
 
 use parser::{SyntaxKind, T};
-use syntax::{TextRange, TextSize};
+use rustc_hash::FxHashMap;
+use syntax::{ast, TextRange, TextSize};
 
 use crate::syntax_bridge::SyntheticTokenId;
 
+#[cfg(feature = "serde")]
+mod serde_impls;
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 enum TokenTextRange {
     Token(TextRange),
@@ -34,6 +38,82 @@ pub struct TokenMap {
     /// Maps `tt::TokenId` to the *relative* source range.
     entries: Vec<(tt::TokenId, TokenTextRange)>,
     pub synthetic_entries: Vec<(tt::TokenId, SyntheticTokenId)>,
+    /// *Relative* ranges of the comments of the input, only filled in when
+    /// requested via `ConvertConfig::record_comment_ranges`.
+    comment_ranges: Vec<TextRange>,
+    /// Maps the ids of desugared doc comment literals to the kind of the original
+    /// comment, only filled in when requested via
+    /// `ConvertConfig::record_doc_comment_kinds`.
+    doc_comment_kinds: Vec<(tt::TokenId, ast::CommentKind)>,
+    /// *Relative* ranges of the items directly inside the converted node, only
+    /// filled in when requested via `ConvertConfig::record_item_ranges`.
+    item_ranges: Vec<TextRange>,
+    /// Maps the ids of keywords to their kind, only filled in when requested via
+    /// `ConvertConfig::record_keyword_kinds`.
+    keyword_kinds: Vec<(tt::TokenId, SyntaxKind)>,
+    /// Maps the ids of delimiters with whitespace inside to where it was, only
+    /// filled in when requested via `ConvertConfig::record_delimiter_spacing`.
+    delimiter_spacing: Vec<(tt::TokenId, DelimiterSpacing)>,
+    /// Number of synthetic tokens converted into this map.
+    synthetic_token_count: usize,
+}
+
+/// Whether the source had whitespace right inside a pair of delimiters, see
+/// [`TokenMap::delimiter_spacing`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash)]
+pub struct DelimiterSpacing {
+    /// Whitespace follows the opening delimiter, as in `( x)`.
+    pub after_open: bool,
+    /// Whitespace precedes the closing delimiter, as in `(x )`.
+    pub before_close: bool,
+}
+
+/// The differences between two [`TokenMap`]s, see [`TokenMap::diff`].
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct TokenMapDiff {
+    /// Ids only mapped by the new map.
+    pub added: Vec<tt::TokenId>,
+    /// Ids only mapped by the old map.
+    pub removed: Vec<tt::TokenId>,
+    /// Ids mapped by both maps, but to different ranges, with their old and new range.
+    ///
+    /// The range of a delimiter covers both the opening and the closing delimiter.
+    pub moved: Vec<(tt::TokenId, TextRange, TextRange)>,
+}
+
+impl TokenMapDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.moved.is_empty()
+    }
+}
+
+/// The range of the source `tt` was converted from, relative like the ranges in
+/// `map`. This spans its delimiters if it has any, and all of its tokens otherwise.
+///
+/// Synthetic tokens are skipped, `None` is returned when there's nothing else.
+pub fn subtree_source_range(tt: &tt::Subtree, map: &TokenMap) -> Option<TextRange> {
+    let is_synthetic = |id| map.synthetic_token_id(id).is_some();
+    if let Some(delim) = tt.delimiter.filter(|it| !is_synthetic(it.id)) {
+        if let Some((open, close)) = map.delimiter_ranges(delim.id) {
+            return Some(open.cover(close));
+        }
+    }
+    tt.token_trees
+        .iter()
+        .filter_map(|tree| match tree {
+            tt::TokenTree::Leaf(leaf) => {
+                let id = leaf.id();
+                if is_synthetic(id) {
+                    return None;
+                }
+                map.entries.iter().find_map(|&(tid, range)| match range {
+                    TokenTextRange::Token(it) if tid == id => Some(it),
+                    _ => None,
+                })
+            }
+            tt::TokenTree::Subtree(subtree) => subtree_source_range(subtree, map),
+        })
+        .reduce(TextRange::cover)
 }
 
 impl TokenMap {
@@ -64,6 +144,15 @@ impl TokenMap {
         self.synthetic_entries.iter().find(|(tid, _)| *tid == token_id).map(|(_, id)| *id)
     }
 
+    /// How many of the synthetic tokens passed to
+    /// `syntax_node_to_token_tree_with_modifications` made it into the token
+    /// trees built with this map, across all conversions that extended it.
+    ///
+    /// Unlike `synthetic_entries`, a lifetime split into two leaves counts once.
+    pub fn synthetic_token_count(&self) -> usize {
+        self.synthetic_token_count
+    }
+
     pub fn first_range_by_token(
         &self,
         token_id: tt::TokenId,
@@ -72,15 +161,237 @@ impl TokenMap {
         self.ranges_by_token(token_id, kind).next()
     }
 
+    /// The token a parse error at `offset` in the text of a tree built by
+    /// `token_tree_to_syntax_node` is about, which is the first token at or after
+    /// `offset`, or the last token for errors at the end of the text.
+    ///
+    /// The id can then be looked up in the map of the macro input, to point at the
+    /// original source instead of the reconstructed text.
+    pub fn token_by_error_offset(&self, offset: TextSize) -> Option<tt::TokenId> {
+        let starts = self.entries.iter().flat_map(|&(id, range)| {
+            let (first, second) = match range {
+                TokenTextRange::Token(it) => (it.start(), None),
+                TokenTextRange::Delimiter(it) => (it.start(), Some(it.end() - TextSize::of('}'))),
+            };
+            std::iter::once((first, id)).chain(second.map(|it| (it, id)))
+        });
+        let (after, before): (Vec<_>, Vec<_>) = starts.partition(|&(start, _)| start >= offset);
+        let closest = match after.into_iter().min_by_key(|&(start, _)| start) {
+            Some(it) => it,
+            None => before.into_iter().max_by_key(|&(start, _)| start)?,
+        };
+        Some(closest.1)
+    }
+
+    /// The tokens overlapping `range` with their ranges, in source order. Both
+    /// delimiters of a pair are included if they overlap, under the same id.
+    ///
+    /// `range` and the returned ranges are offsets into the text, the recorded
+    /// ones are shifted by `offset` first: the start of the converted node, or
+    /// zero for maps recorded with `ConvertConfig::absolute_ranges`.
+    pub fn ranges_by_range(
+        &self,
+        range: TextRange,
+        offset: TextSize,
+    ) -> Vec<(tt::TokenId, TextRange)> {
+        let mut res: Vec<_> = self
+            .entries
+            .iter()
+            .flat_map(|&(id, entry)| {
+                let (first, second) = match entry {
+                    TokenTextRange::Token(it) => (it, None),
+                    TokenTextRange::Delimiter(it) => (
+                        TextRange::at(it.start(), 1.into()),
+                        Some(TextRange::at(it.end() - TextSize::of('}'), 1.into())),
+                    ),
+                };
+                std::iter::once(first).chain(second).map(move |it| (id, it + offset))
+            })
+            .filter(|(_, it)| {
+                it.intersect(range).map_or(false, |common| !common.is_empty() || range.is_empty())
+            })
+            .collect();
+        res.sort_by_key(|(_, it)| it.start());
+        res
+    }
+
+    /// The first token overlapping `range`, see [`TokenMap::ranges_by_range`].
+    pub fn first_by_range(&self, range: TextRange, offset: TextSize) -> Option<tt::TokenId> {
+        self.ranges_by_range(range, offset).first().map(|&(id, _)| id)
+    }
+
+    /// Whether `token_id` belongs to a pair of delimiters rather than to a leaf.
+    pub fn is_delimiter(&self, token_id: tt::TokenId) -> bool {
+        self.delimiter_ranges(token_id).is_some()
+    }
+
+    /// The relative ranges of the opening and the closing delimiter with the given id.
+    pub fn delimiter_ranges(&self, token_id: tt::TokenId) -> Option<(TextRange, TextRange)> {
+        self.entries.iter().find_map(|&(tid, range)| match range {
+            TokenTextRange::Delimiter(it) if tid == token_id => Some((
+                TextRange::at(it.start(), 1.into()),
+                TextRange::at(it.end() - TextSize::of('}'), 1.into()),
+            )),
+            _ => None,
+        })
+    }
+
+    /// Relative ranges of all comments in the converted input, in source order.
+    pub fn comment_ranges(&self) -> &[TextRange] {
+        &self.comment_ranges
+    }
+
+    /// Relative ranges of the items directly inside the converted node, like the
+    /// top-level items of a file, in source order.
+    pub fn item_ranges(&self) -> &[TextRange] {
+        &self.item_ranges
+    }
+
+    /// The shape and placement of the doc comment the `#[doc = "..."]` literal
+    /// with the given id was desugared from.
+    pub fn doc_comment_kind(&self, token_id: tt::TokenId) -> Option<ast::CommentKind> {
+        self.doc_comment_kinds.iter().find(|(tid, _)| *tid == token_id).map(|&(_, kind)| kind)
+    }
+
+    /// The kind of the keyword the `tt::Ident` with the given id was converted from,
+    /// `None` for identifiers.
+    pub fn keyword_kind(&self, token_id: tt::TokenId) -> Option<SyntaxKind> {
+        self.keyword_kinds.iter().find(|(tid, _)| *tid == token_id).map(|&(_, kind)| kind)
+    }
+
+    /// Where the source had whitespace right inside the delimiters with the given
+    /// id, nowhere for delimiters without any or if it wasn't recorded.
+    pub fn delimiter_spacing(&self, token_id: tt::TokenId) -> DelimiterSpacing {
+        self.delimiter_spacing
+            .iter()
+            .find(|(tid, _)| *tid == token_id)
+            .map_or_else(DelimiterSpacing::default, |&(_, spacing)| spacing)
+    }
+
+    /// Compares the (first) ranges of the ids mapped by `self` and by `other`,
+    /// treating `self` as the old map. All lists are sorted by id.
+    pub fn diff(&self, other: &TokenMap) -> TokenMapDiff {
+        let old = self.ranges_by_id();
+        let new = other.ranges_by_id();
+        let mut res = TokenMapDiff::default();
+        for (&id, &old_range) in &old {
+            match new.get(&id) {
+                None => res.removed.push(id),
+                Some(&new_range) if new_range != old_range => {
+                    res.moved.push((id, old_range, new_range))
+                }
+                Some(_) => (),
+            }
+        }
+        res.added.extend(new.keys().filter(|id| !old.contains_key(id)));
+        res.added.sort_by_key(|id| id.0);
+        res.removed.sort_by_key(|id| id.0);
+        res.moved.sort_by_key(|(id, ..)| id.0);
+        res
+    }
+
+    /// Shifts every range of the map forward by `delta`, so that a map built for
+    /// a node can be used once the node was moved further into the file.
+    ///
+    /// Panics if a range would overflow, leaving the map unchanged.
+    pub fn rebase(&mut self, delta: TextSize) {
+        let shifted = self.shift_ranges(|it| it.checked_add(delta));
+        assert!(shifted, "rebasing the token map by {:?} overflows", delta);
+    }
+
+    /// Shifts every range of the map back by `delta`, the inverse of
+    /// [`TokenMap::rebase`].
+    ///
+    /// Returns `false`, leaving the map unchanged, if a range would start before
+    /// offset 0.
+    pub fn checked_rebase_back(&mut self, delta: TextSize) -> bool {
+        self.shift_ranges(|it| it.checked_sub(delta))
+    }
+
+    /// Applies `shift` to every range, or to none of them if it fails for one.
+    fn shift_ranges(&mut self, shift: impl Fn(TextRange) -> Option<TextRange>) -> bool {
+        let mut ranges = self
+            .entries
+            .iter()
+            .map(|(_, range)| match *range {
+                TokenTextRange::Token(it) | TokenTextRange::Delimiter(it) => it,
+            })
+            .chain(self.comment_ranges.iter().copied())
+            .chain(self.item_ranges.iter().copied());
+        if !ranges.all(|it| shift(it).is_some()) {
+            return false;
+        }
+
+        let shift = |it| shift(it).unwrap();
+        for (_, range) in &mut self.entries {
+            *range = match *range {
+                TokenTextRange::Token(it) => TokenTextRange::Token(shift(it)),
+                TokenTextRange::Delimiter(it) => TokenTextRange::Delimiter(shift(it)),
+            };
+        }
+        for range in self.comment_ranges.iter_mut().chain(&mut self.item_ranges) {
+            *range = shift(*range);
+        }
+        true
+    }
+
+    fn ranges_by_id(&self) -> FxHashMap<tt::TokenId, TextRange> {
+        let mut res = FxHashMap::default();
+        for &(id, range) in &self.entries {
+            let range = match range {
+                TokenTextRange::Token(it) | TokenTextRange::Delimiter(it) => it,
+            };
+            res.entry(id).or_insert(range);
+        }
+        res
+    }
+
     pub(crate) fn shrink_to_fit(&mut self) {
         self.entries.shrink_to_fit();
         self.synthetic_entries.shrink_to_fit();
+        self.comment_ranges.shrink_to_fit();
+        self.doc_comment_kinds.shrink_to_fit();
+        self.item_ranges.shrink_to_fit();
+        self.keyword_kinds.shrink_to_fit();
+        self.delimiter_spacing.shrink_to_fit();
     }
 
     pub(crate) fn insert(&mut self, token_id: tt::TokenId, relative_range: TextRange) {
         self.entries.push((token_id, TokenTextRange::Token(relative_range)));
     }
 
+    pub(crate) fn insert_comment(&mut self, relative_range: TextRange) {
+        self.comment_ranges.push(relative_range);
+    }
+
+    pub(crate) fn insert_item(&mut self, relative_range: TextRange) {
+        self.item_ranges.push(relative_range);
+    }
+
+    pub(crate) fn insert_doc_comment_kind(
+        &mut self,
+        token_id: tt::TokenId,
+        kind: ast::CommentKind,
+    ) {
+        self.doc_comment_kinds.push((token_id, kind));
+    }
+
+    pub(crate) fn insert_keyword_kind(&mut self, token_id: tt::TokenId, kind: SyntaxKind) {
+        self.keyword_kinds.push((token_id, kind));
+    }
+
+    pub(crate) fn insert_delimiter_spacing(
+        &mut self,
+        token_id: tt::TokenId,
+        spacing: DelimiterSpacing,
+    ) {
+        self.delimiter_spacing.push((token_id, spacing));
+    }
+
+    pub(crate) fn count_synthetic_token(&mut self) {
+        self.synthetic_token_count += 1;
+    }
+
     pub(crate) fn insert_synthetic(&mut self, token_id: tt::TokenId, id: SyntheticTokenId) {
         self.synthetic_entries.push((token_id, id));
     }
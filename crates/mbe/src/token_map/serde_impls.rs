@@ -0,0 +1,172 @@
+//! `serde` support for [`TokenMap`], to persist maps across sessions.
+//!
+//! The map is written out as plain integers, so that neither `tt`, `parser` nor
+//! `syntax` have to implement `serde`'s traits. Reading a map back validates
+//! it instead of panicking on the first lookup.
+
+use parser::SyntaxKind;
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use syntax::{ast, TextRange};
+
+use crate::{
+    syntax_bridge::SyntheticTokenId,
+    token_map::{DelimiterSpacing, TokenTextRange},
+    TokenMap,
+};
+
+type Range = (u32, u32);
+
+#[derive(Serialize, Deserialize)]
+struct TokenMapRepr {
+    /// The `bool` is whether the entry is a delimiter.
+    entries: Vec<(u32, bool, Range)>,
+    synthetic_entries: Vec<(u32, SyntheticTokenId)>,
+    comment_ranges: Vec<Range>,
+    /// The kinds are stored as their prefix, e.g. `///`.
+    doc_comment_kinds: Vec<(u32, String)>,
+    item_ranges: Vec<Range>,
+    keyword_kinds: Vec<(u32, u16)>,
+    /// Whitespace after the opening and before the closing delimiter.
+    delimiter_spacing: Vec<(u32, bool, bool)>,
+    synthetic_token_count: usize,
+}
+
+impl Serialize for TokenMap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let range = |it: TextRange| (it.start().into(), it.end().into());
+        TokenMapRepr {
+            entries: self
+                .entries
+                .iter()
+                .map(|&(id, range_)| match range_ {
+                    TokenTextRange::Token(it) => (id.0, false, range(it)),
+                    TokenTextRange::Delimiter(it) => (id.0, true, range(it)),
+                })
+                .collect(),
+            synthetic_entries: self.synthetic_entries.iter().map(|&(id, it)| (id.0, it)).collect(),
+            comment_ranges: self.comment_ranges.iter().copied().map(range).collect(),
+            doc_comment_kinds: self
+                .doc_comment_kinds
+                .iter()
+                .map(|(id, kind)| (id.0, kind.prefix().to_owned()))
+                .collect(),
+            item_ranges: self.item_ranges.iter().copied().map(range).collect(),
+            keyword_kinds: self
+                .keyword_kinds
+                .iter()
+                .map(|&(id, kind)| (id.0, kind.into()))
+                .collect(),
+            delimiter_spacing: self
+                .delimiter_spacing
+                .iter()
+                .map(|&(id, it)| (id.0, it.after_open, it.before_close))
+                .collect(),
+            synthetic_token_count: self.synthetic_token_count,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for TokenMap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<TokenMap, D::Error> {
+        let repr = TokenMapRepr::deserialize(deserializer)?;
+        let range = |(start, end): Range| {
+            if start > end {
+                return Err(D::Error::custom(format!("invalid range {}..{}", start, end)));
+            }
+            Ok(TextRange::new(start.into(), end.into()))
+        };
+        let ranges = |ranges: Vec<Range>| ranges.into_iter().map(range).collect::<Result<_, _>>();
+        Ok(TokenMap {
+            entries: repr
+                .entries
+                .into_iter()
+                .map(|(id, is_delimiter, it)| {
+                    let it = range(it)?;
+                    let it = if is_delimiter {
+                        TokenTextRange::Delimiter(it)
+                    } else {
+                        TokenTextRange::Token(it)
+                    };
+                    Ok((tt::TokenId(id), it))
+                })
+                .collect::<Result<_, _>>()?,
+            synthetic_entries: repr
+                .synthetic_entries
+                .into_iter()
+                .map(|(id, it)| (tt::TokenId(id), it))
+                .collect(),
+            comment_ranges: ranges(repr.comment_ranges)?,
+            doc_comment_kinds: repr
+                .doc_comment_kinds
+                .into_iter()
+                .map(|(id, prefix)| {
+                    if !prefix.starts_with("//") && !prefix.starts_with("/*") {
+                        return Err(D::Error::custom(format!("invalid comment prefix {}", prefix)));
+                    }
+                    Ok((tt::TokenId(id), ast::CommentKind::from_text(&prefix)))
+                })
+                .collect::<Result<_, _>>()?,
+            item_ranges: ranges(repr.item_ranges)?,
+            keyword_kinds: repr
+                .keyword_kinds
+                .into_iter()
+                .map(|(id, kind)| {
+                    if kind > SyntaxKind::__LAST.into() {
+                        return Err(D::Error::custom(format!("invalid syntax kind {}", kind)));
+                    }
+                    Ok((tt::TokenId(id), SyntaxKind::from(kind)))
+                })
+                .collect::<Result<_, _>>()?,
+            delimiter_spacing: repr
+                .delimiter_spacing
+                .into_iter()
+                .map(|(id, after_open, before_close)| {
+                    (tt::TokenId(id), DelimiterSpacing { after_open, before_close })
+                })
+                .collect(),
+            synthetic_token_count: repr.synthetic_token_count,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syntax::{ast, AstNode};
+
+    use crate::{syntax_node_to_token_tree_with_config, ConvertConfig, TokenMap};
+
+    #[test]
+    fn round_trip() {
+        let text = "//! inner\n/// outer\nfn f(a: u8) -> u8 { /* a */ a + 1 }\nstruct S( u8 );";
+        let file = ast::SourceFile::parse(text).ok().unwrap();
+        let config = ConvertConfig {
+            record_comment_ranges: true,
+            record_doc_comment_kinds: true,
+            record_item_ranges: true,
+            record_keyword_kinds: true,
+            record_delimiter_spacing: true,
+            ..ConvertConfig::default()
+        };
+        let (tt, map) = syntax_node_to_token_tree_with_config(file.syntax(), config);
+
+        let json = serde_json::to_string(&map).unwrap();
+        let read: TokenMap = serde_json::from_str(&json).unwrap();
+        assert_eq!(read, map);
+        for id in 0..crate::Shift::new(&tt).0 {
+            let id = tt::TokenId(id);
+            assert_eq!(
+                read.ranges_by_token(id, parser::SyntaxKind::IDENT).collect::<Vec<_>>(),
+                map.ranges_by_token(id, parser::SyntaxKind::IDENT).collect::<Vec<_>>()
+            );
+            assert_eq!(read.doc_comment_kind(id), map.doc_comment_kind(id));
+            assert_eq!(read.keyword_kind(id), map.keyword_kind(id));
+            assert_eq!(read.delimiter_spacing(id), map.delimiter_spacing(id));
+        }
+        // serializing the read map gives the same output
+        assert_eq!(serde_json::to_string(&read).unwrap(), json);
+
+        let invalid = json.replacen("\"keyword_kinds\":[[", "\"keyword_kinds\":[[0,65535],[", 1);
+        assert!(serde_json::from_str::<TokenMap>(&invalid).is_err());
+    }
+}
@@ -0,0 +1,608 @@
+//! Assorted helpers for inspecting `tt::TokenTree`s, which don't depend on
+//! how the trees were produced.
+
+use syntax::{
+    ast::{self, CommentPlacement},
+    AstToken, SmolStr, SyntaxKind, TextRange, TextSize,
+};
+
+/// Groups consecutive joint puncts back into the operators they were split
+/// from, e.g. `=` (joint) followed by `>` (alone) becomes `=>`.
+///
+/// The returned ranges are offsets into the text [`tt::pretty`] produces for
+/// `trees`. Lifetime apostrophes are not operators and are skipped.
+pub fn recombine_puncts(trees: &[tt::TokenTree]) -> Vec<(String, TextRange)> {
+    let mut res = Vec::new();
+    let mut offset = TextSize::default();
+    recombine_puncts_rec(trees, &mut offset, &mut res);
+    res
+}
+
+fn recombine_puncts_rec(
+    trees: &[tt::TokenTree],
+    offset: &mut TextSize,
+    acc: &mut Vec<(String, TextRange)>,
+) {
+    fn flush(
+        current: &mut Option<(String, TextSize)>,
+        end: TextSize,
+        acc: &mut Vec<(String, TextRange)>,
+    ) {
+        if let Some((op, start)) = current.take() {
+            acc.push((op, TextRange::new(start, end)));
+        }
+    }
+
+    let mut current = None;
+    // `tt::pretty` separates every token tree that doesn't follow a joint punct by a space
+    let mut last_joint = true;
+    for tree in trees {
+        if !last_joint {
+            *offset += TextSize::of(' ');
+        }
+        last_joint = false;
+        match tree {
+            tt::TokenTree::Leaf(tt::Leaf::Punct(punct)) if punct.char != '\'' => {
+                let (op, _) = current.get_or_insert_with(|| (String::new(), *offset));
+                op.push(punct.char);
+                *offset += TextSize::of(punct.char);
+                match punct.spacing {
+                    tt::Spacing::Joint => last_joint = true,
+                    tt::Spacing::Alone => flush(&mut current, *offset, acc),
+                }
+            }
+            tt::TokenTree::Leaf(leaf) => {
+                flush(&mut current, *offset, acc);
+                last_joint =
+                    matches!(leaf, tt::Leaf::Punct(tt::Punct { spacing: tt::Spacing::Joint, .. }));
+                *offset += TextSize::of(leaf.to_string().as_str());
+            }
+            tt::TokenTree::Subtree(subtree) => {
+                flush(&mut current, *offset, acc);
+                let delim_len = if subtree.delimiter.is_some() { 1 } else { 0 };
+                *offset += TextSize::from(delim_len);
+                recombine_puncts_rec(&subtree.token_trees, offset, acc);
+                *offset += TextSize::from(delim_len);
+            }
+        }
+    }
+    flush(&mut current, *offset, acc);
+}
+
+/// Callbacks for [`walk`], which does nothing by default.
+pub trait TokenTreeVisitor {
+    fn visit_ident(&mut self, _ident: &tt::Ident) {}
+    fn visit_punct(&mut self, _punct: &tt::Punct) {}
+    fn visit_literal(&mut self, _literal: &tt::Literal) {}
+    /// Called for every nested subtree, before its token trees are visited.
+    /// The subtree passed to [`walk`] itself isn't entered.
+    fn enter_subtree(&mut self, _subtree: &tt::Subtree) {}
+    /// Called for every nested subtree, after its token trees were visited.
+    fn leave_subtree(&mut self, _subtree: &tt::Subtree) {}
+}
+
+/// Visits the token trees of `tt` depth-first, in source order.
+pub fn walk(tt: &tt::Subtree, visitor: &mut impl TokenTreeVisitor) {
+    for tree in &tt.token_trees {
+        match tree {
+            tt::TokenTree::Leaf(tt::Leaf::Ident(it)) => visitor.visit_ident(it),
+            tt::TokenTree::Leaf(tt::Leaf::Punct(it)) => visitor.visit_punct(it),
+            tt::TokenTree::Leaf(tt::Leaf::Literal(it)) => visitor.visit_literal(it),
+            tt::TokenTree::Subtree(subtree) => {
+                visitor.enter_subtree(subtree);
+                walk(subtree, visitor);
+                visitor.leave_subtree(subtree);
+            }
+        }
+    }
+}
+
+/// The idents leading the comma separated parts of `tt`, like the names of
+/// `derive(Clone, serde::Serialize)` where it is `Clone` and `serde`.
+///
+/// Parts that don't start with an ident, e.g. the empty one after a trailing
+/// comma, are skipped. Commas in nested subtrees don't separate parts.
+pub fn tt_to_comma_sep_idents(tt: &tt::Subtree) -> Vec<tt::Ident> {
+    tt.token_trees
+        .split(|it| matches!(it, tt::TokenTree::Leaf(tt::Leaf::Punct(tt::Punct { char: ',', .. }))))
+        .filter_map(|part| match part.first()? {
+            tt::TokenTree::Leaf(tt::Leaf::Ident(ident)) => Some(ident.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Splits `tt` into the token trees before and after the first punct `ch` that
+/// isn't nested in a subtree, like `key` and `value` of `key = value`. The punct
+/// itself is in neither part, and both parts are undelimited.
+///
+/// Puncts are matched one by one, so the first `=` of `==` is a match as well.
+pub fn split_at_punct(tt: &tt::Subtree, ch: char) -> Option<(tt::Subtree, tt::Subtree)> {
+    let idx = tt.token_trees.iter().position(
+        |it| matches!(it, tt::TokenTree::Leaf(tt::Leaf::Punct(punct)) if punct.char == ch),
+    )?;
+    let part =
+        |trees: &[tt::TokenTree]| tt::Subtree { delimiter: None, token_trees: trees.to_vec() };
+    Some((part(&tt.token_trees[..idx]), part(&tt.token_trees[idx + 1..])))
+}
+
+/// Rewrites the idents of `tt` for which `f` returns a new text, keeping the
+/// structure and all other tokens as they are.
+///
+/// The rewritten idents no longer stand for their source token, so they get
+/// fresh ids following the largest id in `tt`.
+pub fn map_idents(
+    mut tt: tt::Subtree,
+    mut f: impl FnMut(&tt::Ident) -> Option<SmolStr>,
+) -> tt::Subtree {
+    let crate::Shift(mut next_id) = crate::Shift::new(&tt);
+    map_idents_rec(&mut tt, &mut f, &mut next_id);
+    tt
+}
+
+fn map_idents_rec(
+    tt: &mut tt::Subtree,
+    f: &mut impl FnMut(&tt::Ident) -> Option<SmolStr>,
+    next_id: &mut u32,
+) {
+    for tree in &mut tt.token_trees {
+        match tree {
+            tt::TokenTree::Leaf(tt::Leaf::Ident(ident)) => {
+                if let Some(text) = f(ident) {
+                    *ident = tt::Ident { text, id: tt::TokenId(*next_id) };
+                    *next_id += 1;
+                }
+            }
+            tt::TokenTree::Leaf(_) => (),
+            tt::TokenTree::Subtree(subtree) => map_idents_rec(subtree, f, next_id),
+        }
+    }
+}
+
+/// Renders `tt` on a single line for debugging conversions, every token
+/// followed by its id and every punct by its spacing, e.g. `a#0 +[J]#1 =[A]#2 (#3 b#4 )`.
+///
+/// Unlike the `Display` output this tells `+=` and `+ =` apart, and it is
+/// terser than the `Debug` output.
+pub fn pretty_print(tt: &tt::Subtree) -> String {
+    let mut res = String::new();
+    pretty_print_rec(tt, &mut res);
+    res
+}
+
+fn pretty_print_rec(tt: &tt::Subtree, buf: &mut String) {
+    let id = |id: tt::TokenId| {
+        if id == tt::TokenId::unspecified() {
+            "#?".to_owned()
+        } else {
+            format!("#{}", id.0)
+        }
+    };
+    let push = |buf: &mut String, text: &str| {
+        if !buf.is_empty() && !buf.ends_with(' ') {
+            buf.push(' ');
+        }
+        buf.push_str(text);
+    };
+    let (open, close) = match tt.delimiter_kind() {
+        Some(tt::DelimiterKind::Parenthesis) => ("(", ")"),
+        Some(tt::DelimiterKind::Brace) => ("{", "}"),
+        Some(tt::DelimiterKind::Bracket) => ("[", "]"),
+        None => ("", ""),
+    };
+    if let Some(delim) = tt.delimiter {
+        push(buf, &format!("{}{}", open, id(delim.id)));
+    }
+    for tree in &tt.token_trees {
+        match tree {
+            tt::TokenTree::Leaf(tt::Leaf::Punct(punct)) => {
+                let spacing = match punct.spacing {
+                    tt::Spacing::Joint => "J",
+                    tt::Spacing::Alone => "A",
+                };
+                push(buf, &format!("{}[{}]{}", punct.char, spacing, id(punct.id)));
+            }
+            tt::TokenTree::Leaf(leaf) => push(buf, &format!("{}{}", leaf, id(leaf.id()))),
+            tt::TokenTree::Subtree(subtree) => pretty_print_rec(subtree, buf),
+        }
+    }
+    if tt.delimiter.is_some() {
+        push(buf, close);
+    }
+}
+
+/// Size metrics of a token tree, see [`token_tree_size`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TokenTreeStats {
+    pub leaves: usize,
+    /// Number of subtrees nested in the measured one, which isn't counted itself.
+    pub subtrees: usize,
+    /// How deeply subtrees are nested, `0` if there are none.
+    pub max_depth: usize,
+}
+
+/// Measures `tt` in a single pass.
+pub fn token_tree_size(tt: &tt::Subtree) -> TokenTreeStats {
+    let mut stats = TokenTreeStats::default();
+    token_tree_size_rec(tt, 0, &mut stats);
+    stats
+}
+
+fn token_tree_size_rec(tt: &tt::Subtree, depth: usize, stats: &mut TokenTreeStats) {
+    stats.max_depth = stats.max_depth.max(depth);
+    for tree in &tt.token_trees {
+        match tree {
+            tt::TokenTree::Leaf(_) => stats.leaves += 1,
+            tt::TokenTree::Subtree(subtree) => {
+                stats.subtrees += 1;
+                token_tree_size_rec(subtree, depth + 1, stats);
+            }
+        }
+    }
+}
+
+/// Compares two token trees while ignoring their `TokenId`s, which differ
+/// between conversions of the same source.
+///
+/// Stops at the first difference, without allocating.
+pub fn tt_structural_eq(a: &tt::Subtree, b: &tt::Subtree) -> bool {
+    a.delimiter_kind() == b.delimiter_kind()
+        && a.token_trees.len() == b.token_trees.len()
+        && a.token_trees.iter().zip(&b.token_trees).all(|pair| match pair {
+            (tt::TokenTree::Leaf(a), tt::TokenTree::Leaf(b)) => match (a, b) {
+                (tt::Leaf::Ident(a), tt::Leaf::Ident(b)) => a.text == b.text,
+                (tt::Leaf::Literal(a), tt::Leaf::Literal(b)) => a.text == b.text,
+                (tt::Leaf::Punct(a), tt::Leaf::Punct(b)) => {
+                    a.char == b.char && a.spacing == b.spacing
+                }
+                _ => false,
+            },
+            (tt::TokenTree::Subtree(a), tt::TokenTree::Subtree(b)) => tt_structural_eq(a, b),
+            _ => false,
+        })
+}
+
+/// Recomputes the spacing of every punct in `tt` with the lookahead rule of
+/// the syntax conversion: a punct is joint if the next token is a punct or a
+/// closing delimiter, and alone otherwise. The `'` of a lifetime stays joint
+/// to its ident, as the lifetime would fall apart otherwise.
+///
+/// Whitespace between two puncts is meaningful (`+=` vs `+ =`) and can't be
+/// recovered from the tree, so the spacing of a punct followed by another one
+/// is left untouched. Everywhere else the spacing never reaches the text
+/// produced by `token_tree_to_syntax_node`, and is normalized so that parsed
+/// and synthesized trees of the same code compare equal.
+pub fn canonicalize_spacing(tt: &mut tt::Subtree) {
+    canonicalize_spacing_rec(tt, NextToken::Other);
+}
+
+#[derive(Clone, Copy)]
+enum NextToken {
+    Punct,
+    CloseDelim,
+    Ident,
+    Other,
+}
+
+/// Walks `tt` backwards, `after` being the token following it. Returns the
+/// kind of its first token.
+fn canonicalize_spacing_rec(tt: &mut tt::Subtree, after: NextToken) -> NextToken {
+    let mut next = if tt.delimiter.is_some() { NextToken::CloseDelim } else { after };
+    for tree in tt.token_trees.iter_mut().rev() {
+        next = match tree {
+            tt::TokenTree::Leaf(tt::Leaf::Punct(punct)) => {
+                match next {
+                    NextToken::Punct => (),
+                    NextToken::CloseDelim => punct.spacing = tt::Spacing::Joint,
+                    NextToken::Ident if punct.char == '\'' => punct.spacing = tt::Spacing::Joint,
+                    NextToken::Ident | NextToken::Other => punct.spacing = tt::Spacing::Alone,
+                }
+                NextToken::Punct
+            }
+            tt::TokenTree::Leaf(tt::Leaf::Ident(_)) => NextToken::Ident,
+            tt::TokenTree::Leaf(tt::Leaf::Literal(_)) => NextToken::Other,
+            tt::TokenTree::Subtree(subtree) => canonicalize_spacing_rec(subtree, next),
+        };
+    }
+    // an opening delimiter never makes the preceding punct joint
+    if tt.delimiter.is_some() {
+        NextToken::Other
+    } else {
+        next
+    }
+}
+
+/// Finds the `#[doc = "..."]` and `#![doc = "..."]` attributes in `tt`, as
+/// produced for doc comments when converting syntax to token trees, and
+/// returns the unescaped comment text of each together with its placement.
+///
+/// Attributes nested in subtrees are included, in source order.
+pub fn extract_doc_comments(tt: &tt::Subtree) -> Vec<(CommentPlacement, String)> {
+    let mut res = Vec::new();
+    extract_doc_comments_rec(&tt.token_trees, &mut res);
+    res
+}
+
+fn extract_doc_comments_rec(trees: &[tt::TokenTree], acc: &mut Vec<(CommentPlacement, String)>) {
+    let mut idx = 0;
+    while idx < trees.len() {
+        if let Some((placement, text, len)) = doc_attr_at(&trees[idx..]) {
+            acc.push((placement, text));
+            idx += len;
+            continue;
+        }
+        if let tt::TokenTree::Subtree(subtree) = &trees[idx] {
+            extract_doc_comments_rec(&subtree.token_trees, acc);
+        }
+        idx += 1;
+    }
+}
+
+/// Matches `# !? [doc = "..."]` at the start of `trees`, returning the number
+/// of token trees it spans.
+fn doc_attr_at(trees: &[tt::TokenTree]) -> Option<(CommentPlacement, String, usize)> {
+    let mut trees = trees.iter();
+    match trees.next()? {
+        tt::TokenTree::Leaf(tt::Leaf::Punct(tt::Punct { char: '#', .. })) => (),
+        _ => return None,
+    }
+    let (placement, meta, len) = match trees.next()? {
+        tt::TokenTree::Leaf(tt::Leaf::Punct(tt::Punct { char: '!', .. })) => match trees.next()? {
+            tt::TokenTree::Subtree(meta) => (CommentPlacement::Inner, meta, 3),
+            _ => return None,
+        },
+        tt::TokenTree::Subtree(meta) => (CommentPlacement::Outer, meta, 2),
+        _ => return None,
+    };
+    if meta.delimiter_kind() != Some(tt::DelimiterKind::Bracket) {
+        return None;
+    }
+    let lit = match &*meta.token_trees {
+        [tt::TokenTree::Leaf(doc), tt::TokenTree::Leaf(eq), tt::TokenTree::Leaf(lit)] => {
+            match (doc, eq, lit) {
+                (
+                    tt::Leaf::Ident(tt::Ident { text, .. }),
+                    tt::Leaf::Punct(tt::Punct { char: '=', .. }),
+                    tt::Leaf::Literal(lit),
+                ) if text == "doc" => lit,
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    let token = ast::String::cast(ast::make::tokens::literal(&lit.text))?;
+    let text = token.value()?.into_owned();
+    Some((placement, text, len))
+}
+
+/// What kind of literal a [`tt::Literal`] is, see [`literal_kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiteralKind {
+    /// A string, raw or not.
+    String,
+    /// A byte string, raw or not.
+    ByteString,
+    Char,
+    Byte,
+    Int,
+    Float,
+    /// The text isn't a single valid literal token.
+    Invalid,
+}
+
+/// Re-lexes the text of `lit` to find out its kind, which the token tree
+/// doesn't keep. A leading `-`, as literals built by proc macros can have, is
+/// accepted for numbers only.
+pub fn literal_kind(lit: &tt::Literal) -> LiteralKind {
+    let (negative, text) = match lit.text.strip_prefix('-') {
+        Some(text) => (true, text),
+        None => (false, &*lit.text),
+    };
+    let lexed = parser::LexedStr::new(text);
+    if lexed.len() != 1 || lexed.error(0).is_some() {
+        return LiteralKind::Invalid;
+    }
+    match lexed.kind(0) {
+        SyntaxKind::INT_NUMBER => LiteralKind::Int,
+        SyntaxKind::FLOAT_NUMBER => LiteralKind::Float,
+        _ if negative => LiteralKind::Invalid,
+        SyntaxKind::STRING => LiteralKind::String,
+        SyntaxKind::BYTE_STRING => LiteralKind::ByteString,
+        SyntaxKind::CHAR => LiteralKind::Char,
+        SyntaxKind::BYTE => LiteralKind::Byte,
+        _ => LiteralKind::Invalid,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_to_token_tree;
+
+    use super::*;
+
+    #[test]
+    fn recombine_operators() {
+        let (tt, _) = parse_to_token_tree("a::b == c => { d += 'e; }").unwrap();
+        let text = tt::pretty(&tt.token_trees);
+        let ops: Vec<_> = recombine_puncts(&tt.token_trees)
+            .into_iter()
+            .map(|(op, range)| {
+                assert_eq!(op, &text[range]);
+                op
+            })
+            .collect();
+        assert_eq!(ops, ["::", "==", "=>", "+=", ";"]);
+    }
+
+    #[test]
+    fn split_at_top_level_punct() {
+        let split = |text, ch| {
+            let (tt, _) = parse_to_token_tree(text).unwrap();
+            split_at_punct(&tt, ch).map(|(before, after)| (before.to_string(), after.to_string()))
+        };
+        assert_eq!(
+            split(r#"key = f(a = b) = "c""#, '='),
+            Some(("key".to_owned(), r#"f (a = b) = "c""#.to_owned()))
+        );
+        assert_eq!(split("(a, b), c", ','), Some(("(a , b)".to_owned(), "c".to_owned())));
+        assert_eq!(split("= a", '='), Some((String::new(), "a".to_owned())));
+        assert_eq!(split("a [b = c]", '='), None);
+    }
+
+    #[test]
+    fn comma_sep_idents() {
+        let idents = |text| {
+            let (tt, _) = parse_to_token_tree(text).unwrap();
+            tt_to_comma_sep_idents(&tt).into_iter().map(|it| it.text).collect::<Vec<_>>()
+        };
+        assert_eq!(idents("Clone, serde::Serialize, (a, b), Debug,"), ["Clone", "serde", "Debug"]);
+        assert_eq!(idents("feature = \"x\", ,test"), ["feature", "test"]);
+        assert!(idents("").is_empty());
+    }
+
+    #[test]
+    fn rename_idents() {
+        let (tt, _) = parse_to_token_tree("foo + bar(foo)").unwrap();
+        let tt = map_idents(tt, |ident| (ident.text == "foo").then(|| "bar".into()));
+        assert_eq!(pretty_print(&tt), "bar#5 +[A]#1 bar#2 (#3 bar#6 )");
+    }
+
+    #[test]
+    fn pretty_print_spacing_and_ids() {
+        let (tt, _) = parse_to_token_tree("a += f('b, \"c\")").unwrap();
+        assert_eq!(pretty_print(&tt), "a#0 +[J]#1 =[A]#2 f#3 (#4 '[J]#5 b#6 ,[A]#7 \"c\"#8 )");
+    }
+
+    #[test]
+    fn canonical_spacing() {
+        let canonical = |text| {
+            let (mut tt, _) = parse_to_token_tree(text).unwrap();
+            canonicalize_spacing(&mut tt);
+            pretty_print(&tt)
+        };
+        assert_eq!(canonical("(a+ ) - b"), "(#0 a#1 +[J]#2 ) -[A]#3 b#4");
+        assert_eq!(canonical("(a+) -b"), "(#0 a#1 +[J]#2 ) -[A]#3 b#4");
+        assert_eq!(canonical("a + = b += c"), "a#0 +[A]#1 =[A]#2 b#3 +[J]#4 =[A]#5 c#6");
+
+        let (mut synthesized, _) = parse_to_token_tree("x- [y]").unwrap();
+        for tree in &mut synthesized.token_trees {
+            if let tt::TokenTree::Leaf(tt::Leaf::Punct(punct)) = tree {
+                punct.spacing = tt::Spacing::Joint;
+            }
+        }
+        canonicalize_spacing(&mut synthesized);
+        let (parsed, _) = parse_to_token_tree("x - [y]").unwrap();
+        assert_eq!(synthesized, parsed);
+    }
+
+    #[test]
+    fn canonical_spacing_keeps_lifetimes() {
+        let (mut tt, _) = parse_to_token_tree("&'a T").unwrap();
+        canonicalize_spacing(&mut tt);
+        assert_eq!(pretty_print(&tt), "&[A]#0 '[J]#1 a#2 T#3");
+
+        let (parse, _) = crate::token_tree_to_syntax_node(&tt, parser::TopEntryPoint::Type);
+        assert!(parse.errors().is_empty(), "{:?}", parse.errors());
+        assert_eq!(parse.syntax_node().to_string(), "& 'a T");
+        assert!(parse.syntax_node().descendants().any(|it| it.kind() == SyntaxKind::LIFETIME));
+    }
+
+    #[test]
+    fn size_of_token_tree() {
+        let (tt, _) = parse_to_token_tree("fn f(a: u8) { g([a]) }").unwrap();
+        assert_eq!(token_tree_size(&tt), TokenTreeStats { leaves: 7, subtrees: 4, max_depth: 3 });
+        assert_eq!(tt.count(), 11);
+    }
+
+    #[test]
+    fn visit_token_trees() {
+        #[derive(Default)]
+        struct Collect {
+            events: Vec<String>,
+        }
+        impl TokenTreeVisitor for Collect {
+            fn visit_ident(&mut self, ident: &tt::Ident) {
+                self.events.push(ident.text.to_string());
+            }
+            fn visit_literal(&mut self, literal: &tt::Literal) {
+                self.events.push(literal.text.to_string());
+            }
+            fn enter_subtree(&mut self, subtree: &tt::Subtree) {
+                self.events.push(format!("enter {:?}", subtree.delimiter_kind()));
+            }
+            fn leave_subtree(&mut self, _: &tt::Subtree) {
+                self.events.push("leave".to_string());
+            }
+        }
+
+        let (tt, _) = parse_to_token_tree("f(a, [b]) + 1").unwrap();
+        let mut visitor = Collect::default();
+        walk(&tt, &mut visitor);
+        assert_eq!(
+            visitor.events,
+            [
+                "f",
+                "enter Some(Parenthesis)",
+                "a",
+                "enter Some(Bracket)",
+                "b",
+                "leave",
+                "leave",
+                "1"
+            ]
+        );
+    }
+
+    #[test]
+    fn structural_eq_ignores_ids() {
+        let (a, _) = parse_to_token_tree("fn f(x: u8) -> u8 { x + 1 }").unwrap();
+        let (mut b, _) = parse_to_token_tree("  fn f(x:u8)->u8{x+1}").unwrap();
+        b.delimiter = Some(tt::Delimiter { id: tt::TokenId(42), kind: tt::DelimiterKind::Brace });
+        assert!(!tt_structural_eq(&a, &b));
+        b.delimiter = None;
+        assert!(tt_structural_eq(&a, &b));
+
+        let (c, _) = parse_to_token_tree("fn f(x: u8) -> u8 { x += 1 }").unwrap();
+        assert!(!tt_structural_eq(&a, &c));
+        let (d, _) = parse_to_token_tree("fn f(x: u8) -> u8 [ x + 1 ]").unwrap();
+        assert!(!tt_structural_eq(&a, &d));
+    }
+
+    #[test]
+    fn doc_comments_round_trip() {
+        let (tt, _) = parse_to_token_tree(
+            "//! inner \"quoted\"\n/// outer\tline\nmod m { /** block */ fn f() {} }\n#[doc(hidden)] struct S;",
+        )
+        .unwrap();
+        assert_eq!(
+            extract_doc_comments(&tt),
+            [
+                (CommentPlacement::Inner, " inner \"quoted\"".to_string()),
+                (CommentPlacement::Outer, " outer\tline".to_string()),
+                (CommentPlacement::Outer, " block ".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn literal_kinds() {
+        let kind =
+            |text: &str| literal_kind(&tt::Literal { text: text.into(), id: tt::TokenId(0) });
+        assert_eq!(kind(r#""a""#), LiteralKind::String);
+        assert_eq!(kind(r##"r#"a"#"##), LiteralKind::String);
+        assert_eq!(kind(r#"b"a""#), LiteralKind::ByteString);
+        assert_eq!(kind("'a'"), LiteralKind::Char);
+        assert_eq!(kind("b'a'"), LiteralKind::Byte);
+        assert_eq!(kind("1u8"), LiteralKind::Int);
+        assert_eq!(kind("0x1f"), LiteralKind::Int);
+        assert_eq!(kind("-1"), LiteralKind::Int);
+        assert_eq!(kind("1.5e3f32"), LiteralKind::Float);
+        assert_eq!(kind("-1.5"), LiteralKind::Float);
+
+        assert_eq!(kind(r#"-"a""#), LiteralKind::Invalid);
+        assert_eq!(kind(r#""unterminated"#), LiteralKind::Invalid);
+        assert_eq!(kind("1 2"), LiteralKind::Invalid);
+        assert_eq!(kind("true"), LiteralKind::Invalid);
+        assert_eq!(kind(""), LiteralKind::Invalid);
+    }
+}
@@ -0,0 +1,14 @@
+//! The edition of the Rust language, as far as lexing is concerned.
+
+/// Rust editions which change how text is split into tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Edition {
+    Edition2015,
+    Edition2018,
+    Edition2021,
+    Edition2024,
+}
+
+impl Edition {
+    pub const LATEST: Edition = Edition::Edition2024;
+}
@@ -36,6 +36,14 @@ fn attr(p: &mut Parser, inner: bool) {
 
 pub(super) fn meta(p: &mut Parser) {
     let meta = p.start();
+    // test unsafe_attr
+    // #[unsafe(no_mangle)]
+    // #[unsafe(export_name = "f")]
+    // fn f() {}
+    let is_unsafe = p.eat(T![unsafe]);
+    if is_unsafe {
+        p.expect(T!['(']);
+    }
     paths::use_path(p);
 
     match p.current() {
@@ -49,5 +57,8 @@ pub(super) fn meta(p: &mut Parser) {
         _ => {}
     }
 
+    if is_unsafe {
+        p.expect(T![')']);
+    }
     meta.complete(p, META);
 }
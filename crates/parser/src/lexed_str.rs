@@ -11,6 +11,7 @@
 use std::ops;
 
 use crate::{
+    Edition,
     SyntaxKind::{self, *},
     T,
 };
@@ -29,6 +30,10 @@ struct LexError {
 
 impl<'a> LexedStr<'a> {
     pub fn new(text: &'a str) -> LexedStr<'a> {
+        LexedStr::new_with_edition(text, Edition::LATEST)
+    }
+
+    pub fn new_with_edition(text: &'a str, edition: Edition) -> LexedStr<'a> {
         let mut res = LexedStr { text, kind: Vec::new(), start: Vec::new(), error: Vec::new() };
 
         let mut offset = 0;
@@ -36,12 +41,27 @@ impl<'a> LexedStr<'a> {
             res.push(SHEBANG, offset);
             offset = shebang_len
         };
-        for token in rustc_lexer::tokenize(&text[offset..]) {
+        let tokens: Vec<_> = rustc_lexer::tokenize(&text[offset..]).collect();
+        let mut idx = 0;
+        while let Some(token) = tokens.get(idx) {
             let token_text = &text[offset..][..token.len];
-
             let (kind, err) = from_rustc(&token.kind, token_text);
             res.push(kind, offset);
             offset += token.len;
+            idx += 1;
+
+            // rustc_lexer predates raw lifetimes and splits them into `'r` `#` `foo`,
+            // which is what editions before 2021 want anyway
+            if kind == LIFETIME_IDENT && token_text == "'r" && edition >= Edition::Edition2021 {
+                if let [pound, ident, ..] = &tokens[idx..] {
+                    if pound.kind == rustc_lexer::TokenKind::Pound
+                        && ident.kind == rustc_lexer::TokenKind::Ident
+                    {
+                        offset += pound.len + ident.len;
+                        idx += 2;
+                    }
+                }
+            }
 
             if let Some(err) = err {
                 let token = res.len() as u32;
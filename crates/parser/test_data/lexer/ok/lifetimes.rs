@@ -1 +1 @@
-'a 'foo 'foo_bar_baz '_
+'a 'foo 'foo_bar_baz '_ 'r#foo
@@ -0,0 +1 @@
+'r#
\ No newline at end of file
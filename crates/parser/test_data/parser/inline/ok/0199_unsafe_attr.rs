@@ -0,0 +1,3 @@
+#[unsafe(no_mangle)]
+#[unsafe(export_name = "f")]
+fn f() {}
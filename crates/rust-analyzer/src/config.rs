@@ -170,6 +170,9 @@ config_data! {
         completion_autoself_enable: bool        = "true",
         /// Enables completions of private items and fields that are defined in the current workspace even if they are not visible at the current position.
         completion_privateEditable_enable: bool = "false",
+        /// Names of unstable features to complete in `#![feature(...)]` on top of the ones
+        /// rust-analyzer knows about, like the features of a newer nightly toolchain.
+        completion_extraFeatures: Vec<String> = "[]",
 
         /// Whether to show native rust-analyzer diagnostics.
         diagnostics_enable: bool                = "true",
@@ -893,6 +896,7 @@ impl Config {
                 false
             )),
             snippets: self.snippets.clone(),
+            toolchain_features: self.data.completion_extraFeatures.clone(),
         }
     }
     pub fn assist(&self) -> AssistConfig {
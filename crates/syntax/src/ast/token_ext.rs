@@ -54,13 +54,13 @@ impl ast::Comment {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub struct CommentKind {
     pub shape: CommentShape,
     pub doc: Option<CommentPlacement>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum CommentShape {
     Line,
     Block,
@@ -76,7 +76,7 @@ impl CommentShape {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum CommentPlacement {
     Inner,
     Outer,
@@ -95,7 +95,7 @@ impl CommentKind {
         ("/*", CommentKind { shape: CommentShape::Block, doc: None }),
     ];
 
-    pub(crate) fn from_text(text: &str) -> CommentKind {
+    pub fn from_text(text: &str) -> CommentKind {
         let &(_prefix, kind) = CommentKind::BY_PREFIX
             .iter()
             .find(|&(prefix, _kind)| text.starts_with(prefix))
@@ -52,6 +52,7 @@
 //! - dependencies via `deps:dep1,dep2`
 //! - configuration settings via `cfg:dbg=false,opt_level=2`
 //! - environment variables via `env:PATH=/bin,RUST_LOG=debug`
+//! - the crate type via `crate-type:proc-macro`
 //!
 //! Example using all available metadata:
 //! ```
@@ -76,6 +77,7 @@ pub struct Fixture {
     pub edition: Option<String>,
     pub env: FxHashMap<String, String>,
     pub introduce_new_source_root: Option<String>,
+    pub crate_type: Option<String>,
 }
 
 pub struct MiniCore {
@@ -179,6 +181,7 @@ impl Fixture {
         let mut cfg_key_values = Vec::new();
         let mut env = FxHashMap::default();
         let mut introduce_new_source_root = None;
+        let mut crate_type = None;
         for component in components[1..].iter() {
             let (key, value) = component
                 .split_once(':')
@@ -211,6 +214,7 @@ impl Fixture {
                     }
                 }
                 "new_source_root" => introduce_new_source_root = Some(value.to_string()),
+                "crate-type" => crate_type = Some(value.to_string()),
                 _ => panic!("bad component: {:?}", component),
             }
         }
@@ -235,6 +239,7 @@ impl Fixture {
             edition,
             env,
             introduce_new_source_root,
+            crate_type,
         }
     }
 }